@@ -0,0 +1,183 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::{item_db, wynn_api};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// The full item list is several megabytes, give it longer than a status check.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Issues a GET against `endpoint` and describes the response status, e.g. `200 OK`.
+pub async fn test_connection(endpoint: String) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let response = client
+        .get(&endpoint)
+        .send()
+        .await
+        .map_err(|e| format!("Request to {} failed: {}", endpoint, e))?;
+
+    Ok(response.status().to_string())
+}
+
+/// Source of the item list. The app uses `HttpClient`; tests can hand out a fixture.
+pub trait ItemClient {
+    /// The raw body served at `endpoint`.
+    fn fetch_items(&self, endpoint: &str) -> impl Future<Output = Result<Vec<u8>, String>> + Send;
+}
+
+/// Fetches the item list over HTTP with reqwest.
+pub struct HttpClient;
+
+impl ItemClient for HttpClient {
+    async fn fetch_items(&self, endpoint: &str) -> Result<Vec<u8>, String> {
+        let client = reqwest::Client::builder()
+            .timeout(DOWNLOAD_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        let response = client
+            .get(endpoint)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| format!("Request to {} failed: {}", endpoint, e))?;
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to download items: {}", e))?;
+        Ok(body.to_vec())
+    }
+}
+
+/// Downloads the item list from `endpoint` and, if it parses, replaces `items_path` with it.
+/// An items.json mirror is written as served; the game API's own item database is
+/// converted to items.json's format first. The API has no item ids or set definitions,
+/// so those are carried over from the items.json being replaced.
+pub async fn update_items(
+    client: impl ItemClient,
+    endpoint: String,
+    items_path: PathBuf,
+) -> Result<String, String> {
+    let body = client.fetch_items(&endpoint).await?;
+    let body = match item_db::validate_items(&body) {
+        Ok(_) => body,
+        Err(_) => {
+            // No readable items.json yet just means there's nothing to carry over
+            let (previous, sets) = item_db::read_items(&items_path).unwrap_or_default();
+            let items = wynn_api::convert_items(&body, &previous)?;
+            serde_json::to_vec(&serde_json::json!({ "items": items, "sets": sets })).map_err(|e| e.to_string())?
+        }
+    };
+    let count = item_db::validate_items(&body)?;
+    item_db::replace_items(&items_path, &body)?;
+    Ok(format!("Downloaded {} items", count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    const FIXTURE: &str = r#"{"items": [{"id": 1, "name": "Yang", "tier": "Rare", "type": "ring", "lvl": 65}]}"#;
+
+    struct FixtureClient(&'static str);
+
+    impl ItemClient for FixtureClient {
+        async fn fetch_items(&self, _endpoint: &str) -> Result<Vec<u8>, String> {
+            Ok(self.0.as_bytes().to_vec())
+        }
+    }
+
+    fn items_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("api_{}_{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("items.json")
+    }
+
+    /// Answers a single request with `body`, returning the URL to request.
+    fn serve_once(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/items", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn valid_download_replaces_items_json() {
+        let path = items_path("valid");
+        std::fs::write(&path, "old").unwrap();
+
+        let status = update_items(FixtureClient(FIXTURE), String::new(), path.clone()).await;
+
+        assert_eq!(status, Ok("Downloaded 1 items".to_string()));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), FIXTURE);
+    }
+
+    #[tokio::test]
+    async fn api_item_database_is_converted() {
+        let path = items_path("convert");
+
+        let status = update_items(FixtureClient(wynn_api::tests::API_FIXTURE), String::new(), path.clone()).await;
+
+        assert_eq!(status, Ok("Downloaded 2 items".to_string()));
+        let item_db = item_db::load_blocking(&path, &path.with_file_name("items.db")).unwrap();
+        let names: Vec<&str> = item_db.gear_list.items.iter().map(|gear| gear.name.as_str()).collect();
+        assert_eq!(names, vec!["Cumulonimbus", "Stratiformis"]);
+        assert_eq!(item_db.gear_list.items[0].stat("sdPct"), 15.0);
+    }
+
+    #[tokio::test]
+    async fn api_updates_keep_ids_and_sets() {
+        let path = items_path("convert_again");
+        let previous = r#"{
+            "items": [{"id": 42, "name": "Stratiformis", "tier": "Mythic", "type": "bow"}],
+            "sets": {"Cloud": {"items": ["Cumulonimbus"], "bonuses": [{}]}}
+        }"#;
+        std::fs::write(&path, previous).unwrap();
+
+        update_items(FixtureClient(wynn_api::tests::API_FIXTURE), String::new(), path.clone()).await.unwrap();
+
+        let (gear_list, sets) = item_db::read_items(&path).unwrap();
+        let ids: Vec<i64> = gear_list.items.iter().map(|gear| gear.id).collect();
+        assert_eq!(ids, vec![43, 42]);
+        assert_eq!(sets.set_of(&gear_list.items[0]), Some("Cloud"));
+    }
+
+    #[tokio::test]
+    async fn invalid_download_keeps_the_old_file() {
+        let path = items_path("invalid");
+        std::fs::write(&path, "old").unwrap();
+
+        assert!(update_items(FixtureClient("<html>"), String::new(), path.clone()).await.is_err());
+        assert!(update_items(FixtureClient(r#"{"items": []}"#), String::new(), path.clone()).await.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+    }
+
+    #[tokio::test]
+    async fn http_client_reads_from_a_server() {
+        let path = items_path("http");
+
+        let status = update_items(HttpClient, serve_once(FIXTURE), path.clone()).await;
+
+        assert_eq!(status, Ok("Downloaded 1 items".to_string()));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), FIXTURE);
+    }
+}
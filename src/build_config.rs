@@ -1,8 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::{fs::File, io::Read, path::Path, str};
 
-#[derive(Debug, Deserialize, Clone, Serialize, Default)]
+use crate::config::Class;
+use crate::migrations::{self, CURRENT_VERSION};
+
+#[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct Config {
+    /// Schema version, see `migrations`. Missing in files from before versioning.
+    #[serde(default)]
+    pub version: u32,
     pub items: Items,
     pub player: Player,
     pub hppeng: Hppeng,
@@ -12,8 +18,30 @@ pub struct Config {
     pub threshold_third: Option<ThresholdThird>,
     pub threshold_fourth: Option<ThresholdFourth>,
     pub threshold_fifth: Option<ThresholdFifth>,
+    /// Top-level keys the app doesn't model, kept so saving doesn't drop them.
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
-#[derive(Debug, Deserialize, Clone, Serialize, Default)]
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            items: Items::default(),
+            player: Player::default(),
+            hppeng: Hppeng::default(),
+            api: None,
+            threshold_first: None,
+            threshold_second: None,
+            threshold_third: None,
+            threshold_fourth: None,
+            threshold_fifth: None,
+            extra: toml::Table::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Serialize, Default, PartialEq)]
 pub struct Items {
     pub helmets: Vec<String>,
     pub chest_plates: Vec<String>,
@@ -30,6 +58,9 @@ pub struct Player {
     pub lvl: i32,
     pub available_point: i16,
     pub base_hp: i32,
+    /// Restricts the weapon list to the class's weapon type and hides gear for other classes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<Class>,
 }
 #[derive(Debug, Deserialize, Clone, Serialize, Default)]
 pub struct Hppeng {
@@ -52,6 +83,11 @@ pub struct Api {
 #[derive(Debug, Deserialize, Clone, Serialize, Default)]
 pub struct ThresholdFirst {
     pub min_hp: Option<i32>,
+
+    /// Threshold keys without a dedicated field. They round-trip untouched and
+    /// can still be edited through the descriptors in `config::thresholds`.
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 #[derive(Debug, Deserialize, Clone, Serialize, Default)]
 pub struct ThresholdSecond {
@@ -66,6 +102,9 @@ pub struct ThresholdSecond {
     pub min_exp_bonus: Option<i32>,
 
     pub min_hpr: Option<i32>,
+
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 #[derive(Debug, Deserialize, Clone, Serialize, Default)]
 pub struct ThresholdThird {
@@ -74,6 +113,9 @@ pub struct ThresholdThird {
     pub min_water_defense: Option<i16>,
     pub min_fire_defense: Option<i16>,
     pub min_air_defense: Option<i16>,
+
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 #[derive(Debug, Deserialize, Clone, Serialize, Default)]
 pub struct ThresholdFourth {
@@ -83,6 +125,9 @@ pub struct ThresholdFourth {
     pub min_water_dam_pct: Option<i16>,
     pub min_fire_dam_pct: Option<i16>,
     pub min_air_dam_pct: Option<i16>,
+
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 #[derive(Debug, Deserialize, Clone, Serialize, Default)]
 pub struct ThresholdFifth {
@@ -93,13 +138,56 @@ pub struct ThresholdFifth {
     pub min_air_point: Option<i16>,
 
     pub min_ehp: Option<i32>,
+
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
+impl Api {
+    /// `{url}/{version}/{module}`, followed by `?{query}` when a query is set.
+    pub fn endpoint(&self) -> String {
+        let path = [&self.url, &self.version, &self.module]
+            .iter()
+            .map(|segment| segment.trim_matches('/'))
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        match self.query.trim_start_matches('?') {
+            "" => path,
+            query => format!("{}?{}", path, query),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.url.is_empty()
+            && self.version.is_empty()
+            && self.module.is_empty()
+            && self.query.is_empty()
+    }
+}
+
+impl Items {
+    /// Every item name referenced by the gear pools and the weapon slot.
+    pub fn pooled(&self) -> impl Iterator<Item = &String> {
+        self.helmets
+            .iter()
+            .chain(&self.chest_plates)
+            .chain(&self.leggings)
+            .chain(&self.boots)
+            .chain(&self.rings)
+            .chain(&self.bracelets)
+            .chain(&self.necklaces)
+            .chain(std::iter::once(&self.weapon).filter(|weapon| !weapon.is_empty()))
+    }
 }
 
 pub fn load_config(path: impl AsRef<Path>) -> Result<Config, String> {
-    // Check if the config folder exists
-    let config_folder = path.as_ref().parent().unwrap();
-    if !config_folder.exists() {
-        std::fs::create_dir_all(config_folder).map_err(|e| e.to_string())?;
+    // Check if the config folder exists; a bare file name has an empty parent
+    if let Some(config_folder) = path.as_ref().parent().filter(|folder| !folder.as_os_str().is_empty()) {
+        if !config_folder.exists() {
+            std::fs::create_dir_all(config_folder).map_err(|e| e.to_string())?;
+        }
     }
 
     // Check if the file exists
@@ -107,14 +195,30 @@ pub fn load_config(path: impl AsRef<Path>) -> Result<Config, String> {
         return Err("Config file not found".to_string());
     }
 
-    let mut f = File::open(path).map_err(|e| e.to_string())?;
+    let mut f = File::open(&path).map_err(|e| e.to_string())?;
     let mut buffer = Vec::new();
     f.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
+    let content = str::from_utf8(&buffer).map_err(|e| e.to_string())?;
+
+    let (config, migrated_from) = migrations::parse_config(content)?;
+    // Keep the original around before rewriting it in the new format
+    if let Some(version) = migrated_from {
+        migrations::backup(&path, &format!("v{}", version))?;
+        config.save_config(&path)?;
+    }
+    Ok(config)
+}
 
-    match toml::from_str(str::from_utf8(&buffer).unwrap()) {
-        Ok(ok) => Ok(ok),
-        Err(err) => Err(err.to_string()),
+/// Parses a config of any supported version without touching the file, for configs
+/// that are only looked at, like one being compared against.
+pub fn read_config(path: impl AsRef<Path>) -> Result<Config, String> {
+    let path = path.as_ref();
+    if path.as_os_str().is_empty() {
+        return Err("No config file given".to_string());
     }
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let (config, _) = migrations::parse_config(&content)?;
+    Ok(config)
 }
 
 impl Config {
@@ -123,3 +227,44 @@ impl Config {
         std::fs::write(path, toml_string).map_err(|e| e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::tests::V0_CONFIG;
+
+    #[test]
+    fn read_config_leaves_the_file_alone() {
+        let path = std::env::temp_dir().join(format!("read_config_{}.toml", std::process::id()));
+        std::fs::write(&path, V0_CONFIG).unwrap();
+
+        let config = read_config(&path).unwrap();
+
+        assert_eq!(config.player.lvl, 106);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), V0_CONFIG);
+        let mut backup = path.clone().into_os_string();
+        backup.push(".v0.bak");
+        assert!(!Path::new(&backup).exists());
+        assert!(read_config("").is_err());
+    }
+
+    #[test]
+    fn endpoint_joins_segments_with_single_slashes() {
+        let api = |url: &str, version: &str, module: &str, query: &str| Api {
+            url: url.to_string(),
+            version: version.to_string(),
+            module: module.to_string(),
+            query: query.to_string(),
+        };
+        let cases = [
+            (api("https://api.wynncraft.com", "v3", "item/database", "fullResult"), "https://api.wynncraft.com/v3/item/database?fullResult"),
+            (api("https://api.wynncraft.com/", "/v3/", "/item/database/", "?fullResult"), "https://api.wynncraft.com/v3/item/database?fullResult"),
+            (api("https://example.com/items.json", "", "", ""), "https://example.com/items.json"),
+            (api("https://example.com", "/", "items.json", "?"), "https://example.com/items.json"),
+            (Api::default(), ""),
+        ];
+        for (api, endpoint) in cases {
+            assert_eq!(api.endpoint(), endpoint);
+        }
+    }
+}
@@ -13,7 +13,8 @@ use tokio::{
     select,
 };
 
-use crate::config::style::WARNING;
+use crate::config::style::{ERROR, WARNING};
+use crate::config::ConfigErrors;
 use crate::{BuilderMessage, Message};
 
 #[derive(Default)]
@@ -75,7 +76,7 @@ impl Builder {
         }
     }
 
-    pub fn view(&self) -> Container<Message> {
+    pub fn view<'a>(&'a self, config_errors: &'a ConfigErrors) -> Container<'a, Message> {
         let column = column![
         text("Builder").size(30),
         text("This tab is where the builder binary is run and monitored.").size(20),
@@ -92,9 +93,25 @@ impl Builder {
             Element::new(
                 button("Start Builder")
                     .padding(10)
-                    .on_press(Message::Builder(BuilderMessage::StartBinary)),
+                    .on_press_maybe(
+                        config_errors
+                            .is_empty()
+                            .then_some(Message::Builder(BuilderMessage::StartBinary)),
+                    ),
             )
         },
+        // List what has to be fixed in the Config File tab before a run
+        column(config_errors.iter().map(|(field, error)| {
+            text(format!("{}: {}", field, error)).color(ERROR).into()
+        }))
+        .push_maybe((!config_errors.is_empty()).then(|| {
+            text(format!(
+                "Fix the {} config error(s) above in the Config File tab to start the builder.",
+                config_errors.len()
+            ))
+            .size(20)
+            .color(ERROR)
+        })),
             scrollable(column(self.state.text.iter().map(|s| text(s).into())))
                 .width(Length::Fill)
         ]
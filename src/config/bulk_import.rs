@@ -0,0 +1,271 @@
+use super::{GearList, GearSelections, GearSlot};
+
+/// Alphabet of wynnbuilder's URL-safe base64 integers.
+const BASE64_DIGITS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz+-";
+/// Items encoded in a build hash: helmet, chestplate, leggings, boots,
+/// two rings, bracelet, necklace and weapon.
+const BUILD_ITEM_COUNT: usize = 9;
+/// wynnbuilder gives its empty-slot placeholder items ids from 10000 up.
+const EMPTY_SLOT_ID: i64 = 10000;
+
+/// Pasted item names sorted into the pools they belong to.
+#[derive(Debug, Default, Clone)]
+pub struct ImportPlan {
+    pub pools: Vec<(GearSlot, String)>,
+    pub weapon: Option<String>,
+    pub unknown: Vec<String>,
+}
+
+/// Outcome of applying an `ImportPlan`, shown under the paste box.
+#[derive(Debug, Default, Clone)]
+pub struct ImportReport {
+    pub added: Vec<String>,
+    pub already_pooled: Vec<String>,
+    pub unknown: Vec<String>,
+    /// Weapon that was selected before the import picked another.
+    pub replaced_weapon: Option<String>,
+}
+
+/// Adds the planned items to the pools and selects the planned weapon.
+pub fn apply(plan: ImportPlan, gear: &mut GearSelections) -> ImportReport {
+    let mut report = ImportReport {
+        unknown: plan.unknown,
+        ..Default::default()
+    };
+
+    for (slot, name) in plan.pools {
+        if gear.insert(slot, name.clone()) {
+            report.added.push(name);
+        } else {
+            report.already_pooled.push(name);
+        }
+    }
+    if let Some(weapon) = plan.weapon {
+        match gear.selected_weapon.replace(weapon.clone()) {
+            Some(previous) if previous == weapon => report.already_pooled.push(weapon),
+            previous => {
+                report.replaced_weapon = previous;
+                report.added.push(weapon);
+            }
+        }
+    }
+    report
+}
+
+/// Accepts newline or comma separated item names, or a wynnbuilder build URL/hash.
+pub fn plan(input: &str, gear_list: &GearList) -> ImportPlan {
+    let names = match build_hash(input) {
+        Some(hash) => match decode_build_hash(hash) {
+            Ok(ids) => return plan_ids(&ids, gear_list),
+            Err(e) => {
+                return ImportPlan {
+                    unknown: vec![e],
+                    ..Default::default()
+                }
+            }
+        },
+        None => parse_names(input),
+    };
+
+    let mut plan = ImportPlan::default();
+    for name in names {
+        // items.json names are matched case-insensitively so pasted lists don't need exact casing
+        let gear = gear_list
+            .find(&name)
+            .or_else(|| gear_list.items.iter().find(|gear| gear.name.eq_ignore_ascii_case(&name)));
+
+        match gear {
+            Some(gear) if gear.gear_type.is_weapon() => plan.weapon = Some(gear.name.clone()),
+            Some(gear) => match GearSlot::from_gear_type(&gear.gear_type) {
+                Some(slot) => plan.pools.push((slot, gear.name.clone())),
+                None => plan.unknown.push(name),
+            },
+            None => plan.unknown.push(name),
+        }
+    }
+    plan
+}
+
+fn plan_ids(ids: &[i64], gear_list: &GearList) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+    for id in ids.iter().filter(|id| **id < EMPTY_SLOT_ID) {
+        match gear_list.items.iter().find(|gear| gear.id == *id) {
+            Some(gear) if gear.gear_type.is_weapon() => plan.weapon = Some(gear.name.clone()),
+            Some(gear) => match GearSlot::from_gear_type(&gear.gear_type) {
+                Some(slot) => plan.pools.push((slot, gear.name.clone())),
+                None => plan.unknown.push(format!("item id {}", id)),
+            },
+            None => plan.unknown.push(format!("item id {}", id)),
+        }
+    }
+    plan
+}
+
+pub fn parse_names(input: &str) -> Vec<String> {
+    input
+        .split(['\n', ','])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The `<version>_<data>` part of a wynnbuilder URL or a bare hash, if `input` is one.
+fn build_hash(input: &str) -> Option<&str> {
+    let input = input.trim();
+    let hash = match input.rsplit_once('#') {
+        Some((_, hash)) => hash,
+        None => input,
+    };
+
+    let (version, data) = hash.split_once('_')?;
+    let is_hash = !version.is_empty()
+        && version.chars().all(|c| c.is_ascii_digit())
+        && !data.is_empty()
+        && !data.contains(char::is_whitespace);
+    is_hash.then_some(hash)
+}
+
+/// Decodes the item ids of a legacy (version 1 to 9) wynnbuilder build hash.
+pub fn decode_build_hash(hash: &str) -> Result<Vec<i64>, String> {
+    let (version, data) = hash
+        .split_once('_')
+        .ok_or_else(|| "Build hash is missing its version".to_string())?;
+    let version: u32 = version
+        .parse()
+        .map_err(|_| format!("Invalid build hash version \"{}\"", version))?;
+    if !(1..=9).contains(&version) {
+        return Err(format!("Build hash version {} is not supported", version));
+    }
+
+    let mut ids = Vec::with_capacity(BUILD_ITEM_COUNT);
+    let mut rest = data;
+    for _ in 0..BUILD_ITEM_COUNT {
+        // Crafted and custom items don't reference items.json and can't be pooled
+        if rest.starts_with("CR-") || rest.get(3..6) == Some("CI-") || rest.starts_with('-') {
+            return Err("Build hashes with crafted or custom items are not supported".to_string());
+        }
+
+        let code = rest
+            .get(..3)
+            .ok_or_else(|| "Build hash is too short".to_string())?;
+        ids.push(base64_to_int(code)?);
+        rest = &rest[3..];
+    }
+    Ok(ids)
+}
+
+fn base64_to_int(code: &str) -> Result<i64, String> {
+    code.chars().try_fold(0, |acc, c| {
+        BASE64_DIGITS
+            .find(c)
+            .map(|digit| acc * 64 + digit as i64)
+            .ok_or_else(|| format!("Invalid character '{}' in build hash", c))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list, names};
+    use crate::config::{Gear, GearType};
+
+    fn items() -> GearList {
+        gear_list([
+            Gear { id: 64, ..gear(GearType::Helmet, "Cumulonimbus") },
+            Gear { id: 65, ..gear(GearType::Ring, "Yang") },
+            Gear { id: 4096, ..gear(GearType::Wand, "Nirvana") },
+        ])
+    }
+
+    #[test]
+    fn names_are_matched_ignoring_case_and_blanks() {
+        let plan = plan(" cumulonimbus ,, Yang\n\n\nNIRVANA\n", &items());
+
+        assert_eq!(
+            plan.pools,
+            vec![(GearSlot::Helmet, "Cumulonimbus".to_string()), (GearSlot::Ring, "Yang".to_string())]
+        );
+        assert_eq!(plan.weapon.as_deref(), Some("Nirvana"));
+        assert!(plan.unknown.is_empty());
+    }
+
+    #[test]
+    fn empty_input_plans_nothing() {
+        for input in ["", "  \n ", ",\n,"] {
+            let plan = plan(input, &items());
+            assert!(plan.pools.is_empty() && plan.weapon.is_none() && plan.unknown.is_empty(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn duplicates_are_kept_for_the_import_to_report() {
+        let plan = plan("Yang\nyang", &items());
+
+        assert_eq!(plan.pools, vec![(GearSlot::Ring, "Yang".to_string()); 2]);
+    }
+
+    #[test]
+    fn unknown_names_keep_their_spelling() {
+        let plan = plan("Missingno, Some_Item", &items());
+
+        assert!(plan.pools.is_empty());
+        assert_eq!(plan.unknown, names(&["Missingno", "Some_Item"]));
+    }
+
+    #[test]
+    fn legacy_build_hash_is_decoded() {
+        // 64 = "010", 65 = "011", 4096 = "100", 10000 = "2SG" (empty slot), 1 = "001" (not in items.json)
+        let hash = "https://wynnbuilder.github.io/builder/#4_0102SG2SG0010110112SG2SG100000";
+        let plan = plan(hash, &items());
+
+        assert_eq!(
+            plan.pools,
+            vec![
+                (GearSlot::Helmet, "Cumulonimbus".to_string()),
+                (GearSlot::Ring, "Yang".to_string()),
+                (GearSlot::Ring, "Yang".to_string()),
+            ]
+        );
+        assert_eq!(plan.weapon.as_deref(), Some("Nirvana"));
+        assert_eq!(plan.unknown, names(&["item id 1"]));
+    }
+
+    #[test]
+    fn replacing_the_weapon_is_reported() {
+        let items = items();
+        let mut gear = GearSelections::default();
+        gear.selected_weapon = Some("Old Wand".to_string());
+
+        let report = apply(plan("Yang, Nirvana", &items), &mut gear);
+        assert_eq!(report.added, names(&["Yang", "Nirvana"]));
+        assert_eq!(report.replaced_weapon.as_deref(), Some("Old Wand"));
+        assert_eq!(gear.selected_weapon.as_deref(), Some("Nirvana"));
+
+        // Importing the selected weapon again replaces nothing
+        let report = apply(plan("Nirvana", &items), &mut gear);
+        assert_eq!(report.already_pooled, names(&["Nirvana"]));
+        assert_eq!(report.replaced_weapon, None);
+
+        let mut gear = GearSelections::default();
+        assert_eq!(apply(plan("Nirvana", &items), &mut gear).replaced_weapon, None);
+    }
+
+    #[test]
+    fn malformed_hashes_are_reported() {
+        for hash in [
+            "0_010010010010010010010010010",
+            "10_010010010010010010010010010",
+            "99999999999_010010010010010010010010010",
+            "9_010",
+            "9_010010010010010010010010!!!",
+            "9_CR-aaaaaaaaaaaaaaaaaaaaaaaa",
+            "9_010CI-aaaaaaaaaaaaaaaaaaaaa",
+        ] {
+            assert!(decode_build_hash(hash).is_err(), "{}", hash);
+        }
+
+        let plan = plan("#9_010", &items());
+        assert_eq!(plan.unknown, names(&["Build hash is too short"]));
+    }
+}
@@ -0,0 +1,227 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+use crate::build_config::Config;
+use crate::migrations;
+
+use super::config_diff::{self, FieldDiff};
+use super::{Gear, GearList};
+
+/// Marks the compact string form so it can't be mistaken for TOML.
+const STRING_PREFIX: &str = "wbc1:";
+
+/// A config plus, optionally, the items.json entries it references so it can
+/// be loaded by someone with a different items.json.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bundle {
+    pub config: Config,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<Gear>,
+}
+
+/// An import waiting for the user to confirm it after seeing the differences.
+#[derive(Debug, Clone)]
+pub struct PendingImport {
+    pub bundle: Bundle,
+    pub diff: Vec<FieldDiff>,
+}
+
+impl Bundle {
+    pub fn new(config: &Config, gear_list: &GearList, include_items: bool) -> Self {
+        let items = if include_items {
+            gear_list
+                .items
+                .iter()
+                .filter(|gear| config.items.pooled().any(|name| *name == gear.name))
+                .map(|gear| Gear {
+                    // TOML has no null, so drop stats without a value
+                    stats: gear
+                        .stats
+                        .iter()
+                        .filter(|(_, value)| !value.is_null())
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect(),
+                    ..gear.clone()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            config: config.clone(),
+            items,
+        }
+    }
+
+    /// Writes a plain `config.toml` when there are no items, so the file works
+    /// anywhere a config is expected.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        if self.items.is_empty() {
+            return self.config.save_config(path);
+        }
+        let toml_string = toml::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, toml_string).map_err(|e| e.to_string())
+    }
+
+    /// Reads a bundle or a plain `config.toml`. The file is only read, even when its
+    /// config is from an older version.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        Self::from_toml(&content)
+    }
+
+    /// Deflated TOML as URL-safe base64, small enough to paste in chat.
+    pub fn to_compact(&self) -> Result<String, String> {
+        let toml_string = toml::to_string(self).map_err(|e| e.to_string())?;
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder
+            .write_all(toml_string.as_bytes())
+            .map_err(|e| e.to_string())?;
+        let compressed = encoder.finish().map_err(|e| e.to_string())?;
+        Ok(format!("{}{}", STRING_PREFIX, URL_SAFE_NO_PAD.encode(compressed)))
+    }
+
+    /// Accepts the compact string form, a bundle in TOML, or a pasted `config.toml`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let Some(encoded) = input.strip_prefix(STRING_PREFIX) else {
+            return Self::from_toml(input);
+        };
+
+        let compressed = URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|e| format!("Invalid config string: {}", e))?;
+        let mut toml_string = String::new();
+        DeflateDecoder::new(compressed.as_slice())
+            .read_to_string(&mut toml_string)
+            .map_err(|e| format!("Invalid config string: {}", e))?;
+        Self::from_toml(&toml_string)
+    }
+
+    /// A bundle has its config under `[config]`, anything else is a plain config. Either
+    /// way the config goes through `migrations`, so older versions are upgraded.
+    fn from_toml(text: &str) -> Result<Self, String> {
+        let mut table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        let Some(toml::Value::Table(config)) = table.remove("config") else {
+            let (config, _) = migrations::config_from_table(table)?;
+            return Ok(Self {
+                config,
+                items: Vec::new(),
+            });
+        };
+
+        let (config, _) = migrations::config_from_table(config)?;
+        let items = match table.remove("items") {
+            Some(items) => items.try_into().map_err(|e: toml::de::Error| format!("Invalid bundled items: {}", e))?,
+            None => Vec::new(),
+        };
+        Ok(Self { config, items })
+    }
+
+    pub fn preview(self, current: &Config) -> PendingImport {
+        PendingImport {
+            diff: config_diff::diff(current, &self.config),
+            bundle: self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list, names};
+    use crate::config::GearType;
+    use crate::migrations::{tests::V0_CONFIG, CURRENT_VERSION};
+
+    /// `V0_CONFIG` nested in a bundle, written by a build from before versioning.
+    fn v0_bundle() -> String {
+        let config: toml::Table = toml::from_str(V0_CONFIG).unwrap();
+        let bundle = toml::Table::from_iter([("config".to_string(), toml::Value::Table(config))]);
+        toml::to_string(&bundle).unwrap()
+    }
+
+    fn compact(text: &[u8]) -> String {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(text).unwrap();
+        format!("{}{}", STRING_PREFIX, URL_SAFE_NO_PAD.encode(encoder.finish().unwrap()))
+    }
+
+    fn bundle() -> Bundle {
+        let mut config = Config::default();
+        config.player.lvl = 106;
+        config.items.helmets = names(&["Cumulonimbus"]);
+        let mut helmet = Gear { hp: 3000, ..gear(GearType::Helmet, "Cumulonimbus") };
+        helmet.stats.insert("sdPct".to_string(), serde_json::Value::Null);
+
+        Bundle::new(&config, &gear_list([helmet, gear(GearType::Ring, "Yang")]), true)
+    }
+
+    #[test]
+    fn only_referenced_items_are_bundled() {
+        let bundle = bundle();
+
+        assert_eq!(bundle.items.len(), 1);
+        assert_eq!(bundle.items[0].name, "Cumulonimbus");
+        assert!(!bundle.items[0].stats.contains_key("sdPct"));
+    }
+
+    #[test]
+    fn compact_string_round_trips() {
+        let encoded = bundle().to_compact().unwrap();
+        let decoded = Bundle::parse(&encoded).unwrap();
+
+        assert_eq!(decoded.config.player.lvl, 106);
+        assert_eq!(decoded.items[0].stat("hp"), 3000.0);
+    }
+
+    #[test]
+    fn pasted_config_toml_is_accepted() {
+        let toml_string = toml::to_string(&bundle().config).unwrap();
+
+        let parsed = Bundle::parse(&toml_string).unwrap();
+
+        assert_eq!(parsed.config.items.helmets, names(&["Cumulonimbus"]));
+        assert!(parsed.items.is_empty());
+    }
+
+    #[test]
+    fn unversioned_bundles_are_migrated() {
+        for bundle in [Bundle::parse(&compact(v0_bundle().as_bytes())).unwrap(), Bundle::parse(&v0_bundle()).unwrap()] {
+            assert_eq!(bundle.config.version, CURRENT_VERSION);
+            assert_eq!(bundle.config.player.lvl, 106);
+        }
+    }
+
+    #[test]
+    fn malformed_text_is_refused() {
+        let inputs = [
+            String::new(),
+            "wbc1:".to_string(),
+            "wbc1:not base64!".to_string(),
+            // Valid base64, but not deflated
+            format!("{}{}", STRING_PREFIX, URL_SAFE_NO_PAD.encode("config = 1")),
+            compact(b"not = [toml"),
+            compact(&[0xff, 0xfe]),
+            "[config]\nplayer = 5".to_string(),
+            "[config]\n[[items]]\nname = 1".to_string(),
+            "player = { lvl = \"high\" }".to_string(),
+        ];
+        for input in &inputs {
+            assert!(Bundle::parse(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn loading_never_rewrites_the_file() {
+        let path = std::env::temp_dir().join(format!("bundle_load_{}.toml", std::process::id()));
+        std::fs::write(&path, V0_CONFIG).unwrap();
+
+        assert_eq!(Bundle::load(&path).unwrap().config.version, CURRENT_VERSION);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), V0_CONFIG);
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::build_config::Config;
+
+/// One field that differs between two configs, keyed by its dotted TOML path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub path: String,
+    /// Value in the current config, `None` when the field is absent.
+    pub current: Option<toml::Value>,
+    /// Value in the other config, `None` when the field is absent.
+    pub other: Option<toml::Value>,
+}
+
+impl FieldDiff {
+    pub fn current_text(&self) -> String {
+        display(self.current.as_ref())
+    }
+
+    pub fn other_text(&self) -> String {
+        display(self.other.as_ref())
+    }
+
+    /// Names only in the current and only in the other list, for fields like gear pools
+    /// whose order doesn't matter.
+    pub fn set_difference(&self) -> Option<(Vec<String>, Vec<String>)> {
+        let current = string_set(self.current.as_ref()?)?;
+        let other = string_set(self.other.as_ref()?)?;
+        Some((
+            current.difference(&other).map(|name| name.to_string()).collect(),
+            other.difference(&current).map(|name| name.to_string()).collect(),
+        ))
+    }
+}
+
+fn display(value: Option<&toml::Value>) -> String {
+    value.map_or_else(|| "(unset)".to_string(), ToString::to_string)
+}
+
+/// The value as a set of strings, if it is an array of strings.
+fn string_set(value: &toml::Value) -> Option<BTreeSet<&str>> {
+    value.as_array()?.iter().map(toml::Value::as_str).collect()
+}
+
+/// Every leaf value of the config keyed by path, e.g. `player.lvl` -> `106`.
+pub fn flatten(config: &Config) -> BTreeMap<String, toml::Value> {
+    let mut fields = BTreeMap::new();
+    if let Ok(table) = toml::Table::try_from(config) {
+        flatten_table("", &table, &mut fields);
+    }
+    fields
+}
+
+fn flatten_table(prefix: &str, table: &toml::Table, fields: &mut BTreeMap<String, toml::Value>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            toml::Value::Table(table) => flatten_table(&path, table, fields),
+            value => {
+                fields.insert(path, value.clone());
+            }
+        }
+    }
+}
+
+fn same(current: Option<&toml::Value>, other: Option<&toml::Value>) -> bool {
+    match (current, other) {
+        (Some(current), Some(other)) => match (string_set(current), string_set(other)) {
+            (Some(current), Some(other)) => current == other,
+            _ => current == other,
+        },
+        (current, other) => current == other,
+    }
+}
+
+pub fn diff(current: &Config, other: &Config) -> Vec<FieldDiff> {
+    let mut current = flatten(current);
+    let mut other = flatten(other);
+    let mut paths: Vec<String> = current.keys().chain(other.keys()).cloned().collect();
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let current = current.remove(&path);
+            let other = other.remove(&path);
+            (!same(current.as_ref(), other.as_ref())).then_some(FieldDiff { path, current, other })
+        })
+        .collect()
+}
+
+/// Returns `config` with the field at `path` set to `value`, or removed when `value` is `None`.
+/// A section left empty by the removal is dropped too.
+pub fn set_field(config: &Config, path: &str, value: Option<toml::Value>) -> Result<Config, String> {
+    let mut root = toml::Table::try_from(config).map_err(|e| e.to_string())?;
+    let keys: Vec<&str> = path.split('.').collect();
+    let (last, parents) = keys.split_last().ok_or_else(|| "Empty field path".to_string())?;
+
+    let mut table = &mut root;
+    for key in parents {
+        table = table
+            .entry(key.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| format!("{} is not a section", key))?;
+    }
+    match value {
+        Some(value) => {
+            table.insert(last.to_string(), value);
+        }
+        None => {
+            table.remove(*last);
+            if table.is_empty() {
+                if let Some(section) = parents.first() {
+                    root.remove(*section);
+                }
+            }
+        }
+    }
+
+    root.try_into()
+        .map_err(|e: toml::de::Error| format!("Can't set {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::names;
+    use crate::config::thresholds::field;
+    use crate::config::ThresholdSection;
+
+    #[test]
+    fn identical_configs_have_no_diff() {
+        let mut config = Config::default();
+        config.player.lvl = 106;
+        config.items.rings = names(&["Yang"]);
+
+        assert!(diff(&config, &config.clone()).is_empty());
+    }
+
+    #[test]
+    fn only_changed_fields_are_listed() {
+        let mut other = Config::default();
+        other.player.lvl = 106;
+        other.items.helmets = names(&["Cumulonimbus"]);
+        field(ThresholdSection::First, "min_hp").unwrap().set(&mut other, Some(9000)).unwrap();
+
+        let diffs = diff(&Config::default(), &other);
+
+        let paths: Vec<&str> = diffs.iter().map(|diff| diff.path.as_str()).collect();
+        assert_eq!(paths, vec!["items.helmets", "player.lvl", "threshold_first.min_hp"]);
+        assert_eq!((diffs[1].current_text(), diffs[1].other_text()), ("0".to_string(), "106".to_string()));
+        assert_eq!(diffs[2].current_text(), "(unset)");
+    }
+
+    #[test]
+    fn pools_are_compared_as_sets() {
+        let mut current = Config::default();
+        current.items.rings = names(&["Yang", "Moon Pool Circlet"]);
+        let mut other = current.clone();
+        // Order and repeats don't change which items a pool offers
+        other.items.rings = names(&["Moon Pool Circlet", "Yang", "Yang"]);
+        assert!(diff(&current, &other).is_empty());
+
+        other.items.rings = names(&["Moon Pool Circlet", "Diamond Hydro Ring"]);
+        let diffs = diff(&current, &other);
+        assert_eq!(diffs[0].set_difference(), Some((names(&["Yang"]), names(&["Diamond Hydro Ring"]))));
+
+        // An emptied pool is still a list, so both sides are listed
+        other.items.rings = Vec::new();
+        let diffs = diff(&current, &other);
+        assert_eq!(diffs[0].set_difference(), Some((names(&["Moon Pool Circlet", "Yang"]), Vec::new())));
+    }
+
+    #[test]
+    fn fields_can_be_taken_from_either_side() {
+        let mut other = Config::default();
+        other.player.lvl = 106;
+        field(ThresholdSection::First, "min_hp").unwrap().set(&mut other, Some(9000)).unwrap();
+        let diffs = diff(&Config::default(), &other);
+
+        let mut current = Config::default();
+        for diff in &diffs {
+            current = set_field(&current, &diff.path, diff.other.clone()).unwrap();
+        }
+        assert!(super::diff(&current, &other).is_empty());
+
+        // Taking an unset threshold removes the whole section again
+        let current = set_field(&current, "threshold_first.min_hp", None).unwrap();
+        assert!(current.threshold_first.is_none());
+    }
+
+    #[test]
+    fn values_that_dont_fit_are_refused() {
+        let config = Config::default();
+
+        assert!(set_field(&config, "player.lvl", Some(toml::Value::String("high".to_string()))).is_err());
+        assert!(set_field(&config, "player.lvl", Some(toml::Value::Integer(i64::MAX))).is_err());
+        assert!(set_field(&config, "player.lvl.max", Some(toml::Value::Integer(1))).is_err());
+    }
+}
@@ -1,477 +1,461 @@
-use iced::Task;
-use crate::messages::{Message, ConfigMessage, GearMessage, PlayerMessage, ThresholdFirstMessage, ThresholdSecondMessage, ThresholdThirdMessage, ThresholdFourthMessage, ThresholdFifthMessage, HppengMessage};
-use super::ConfigFile;
+use iced::{clipboard, Task};
+use iced_widget::text_editor;
+use crate::api::{self, HttpClient};
+use crate::build_config::Api;
+use crate::item_db::{self, CACHE_PATH, ITEMS_PATH};
+use crate::messages::{Message, ApiMessage, ConfigMessage, BulkImportMessage, FillPoolsMessage, GearFilterMessage, GearMessage, IllegalCombinationMessage, PlayerMessage, HppengMessage, SkillPointMessage, PresetMessage, BundleMessage, CompareMessage, LoadErrorMessage};
+use super::bulk_import;
+use super::bundle::Bundle;
+use super::config_diff;
+use crate::migrations;
+use super::presets::{self, Preset};
+use super::thresholds;
+use super::gear_filter::GearFilter;
+use super::{Comparison, ConfigFile, FillPools, GearSelections};
+use crate::build_config;
 
 impl ConfigFile {
     pub fn update(&mut self, message: ConfigMessage) -> Task<Message> {
         match message {
-            ConfigMessage::Gear(gear_message) => match gear_message {
-                GearMessage::HelmetSelected(idx, name) => {
-                    if let Some(selection) =
-                        self.gear.helmet_selections.get_mut(idx)
-                    {
-                        *selection = Some(name.clone());
-                    }
-                    self.config.items.helmets.push(name);
-                    self.save_config();
-                }
-                GearMessage::AddHelmet => {
-                    self.gear.helmet_selections.push(None);
+            ConfigMessage::Gear(gear_message) => {
+                match gear_message {
+                    GearMessage::Select(slot, idx, name) => {
+                        self.inspected_item = Some(name.clone());
+                        self.gear.select(slot, idx, name);
+                    }
+                    GearMessage::Add(slot) => self.gear.add(slot),
+                    GearMessage::Remove(slot, idx) => self.gear.remove(slot, idx),
+                    GearMessage::WeaponSelected(name) => {
+                        self.inspected_item = Some(name.clone());
+                        self.gear.selected_weapon = Some(name);
+                    }
                 }
-                GearMessage::ChestplateSelected(idx, name) => {
-                    if let Some(selection) =
-                        self.gear.chestplate_selections.get_mut(idx)
-                    {
-                        *selection = Some(name.clone());
+                self.gear.apply_to(&mut self.config.items);
+                self.save_config();
+            }
+            ConfigMessage::IllegalCombination(combination_message) => {
+                let groups = self.config.items.illegal_combinations.get_or_insert_with(Vec::new);
+                match combination_message {
+                    IllegalCombinationMessage::AddGroup(name) => {
+                        groups.push(vec![name]);
+                    }
+                    IllegalCombinationMessage::RemoveGroup(group_idx) => {
+                        if group_idx < groups.len() {
+                            groups.remove(group_idx);
+                        }
+                    }
+                    IllegalCombinationMessage::AddItem(group_idx, name) => {
+                        if let Some(group) = groups.get_mut(group_idx) {
+                            if !group.contains(&name) {
+                                group.push(name);
+                            }
+                        }
+                    }
+                    IllegalCombinationMessage::RemoveItem(group_idx, item_idx) => {
+                        if let Some(group) = groups.get_mut(group_idx) {
+                            if item_idx < group.len() {
+                                group.remove(item_idx);
+                            }
+                        }
+                        groups.retain(|group| !group.is_empty());
                     }
-                    self.config.items.chest_plates.push(name);
-                    self.save_config();
                 }
-                GearMessage::AddChestplate => {
-                    self.gear.chestplate_selections.push(None);
+                // Keep the key out of the TOML entirely when there is nothing to forbid
+                if groups.is_empty() {
+                    self.config.items.illegal_combinations = None;
                 }
-                GearMessage::LeggingsSelected(idx, name) => {
-                    if let Some(selection) =
-                        self.gear.leggings_selections.get_mut(idx)
-                    {
-                        *selection = Some(name.clone());
-                    }
-                    self.config.items.leggings.push(name);
+                self.save_config();
+            }
+            ConfigMessage::BulkImport(import_message) => match import_message {
+                BulkImportMessage::Edit(action) => {
+                    self.bulk_import.perform(action);
+                }
+                BulkImportMessage::Import => {
+                    let plan = bulk_import::plan(&self.bulk_import.text(), &self.gear_list);
+                    self.import_report = Some(bulk_import::apply(plan, &mut self.gear));
+                    self.gear.apply_to(&mut self.config.items);
                     self.save_config();
                 }
-                GearMessage::AddLeggings => {
-                    self.gear.leggings_selections.push(None);
+            },
+            ConfigMessage::FillPools(fill_message) => {
+                let fill = &mut self.fill_pools;
+                match fill_message {
+                    FillPoolsMessage::MinLevelChanged(content) => fill.min_level = content,
+                    FillPoolsMessage::MaxLevelChanged(content) => fill.max_level = content,
+                    FillPoolsMessage::TierToggled(tier, enabled) => {
+                        if enabled {
+                            fill.tiers.insert(tier);
+                        } else {
+                            fill.tiers.remove(&tier);
+                        }
+                    }
+                    FillPoolsMessage::SortStatSelected(stat) => fill.sort_stat = Some(stat),
+                    FillPoolsMessage::CountChanged(content) => {
+                        if content.chars().all(|c| c.is_ascii_digit()) {
+                            fill.count = content;
+                        }
+                    }
+                    FillPoolsMessage::Preview => {
+                        fill.preview = Some(fill.select(&self.gear_list));
+                        return Task::none();
+                    }
+                    FillPoolsMessage::Apply => {
+                        if let Some(Ok(preview)) = fill.preview.take() {
+                            for (slot, names) in preview {
+                                self.gear.set_pool(slot, names);
+                            }
+                            self.gear.apply_to(&mut self.config.items);
+                            self.save_config();
+                        }
+                    }
+                    FillPoolsMessage::Discard => {}
                 }
-                GearMessage::BootsSelected(idx, name) => {
-                    if let Some(selection) =
-                        self.gear.boots_selections.get_mut(idx)
-                    {
-                        *selection = Some(name.clone());
+                // Any criteria change makes the shown preview stale
+                self.fill_pools.preview = None;
+            }
+            ConfigMessage::GearFilter(filter_message) => {
+                let filter = &mut self.gear_filter;
+                match filter_message {
+                    GearFilterMessage::TierToggled(tier, enabled) => {
+                        if enabled {
+                            filter.tiers.insert(tier);
+                        } else {
+                            filter.tiers.remove(&tier);
+                        }
                     }
-                    self.config.items.boots.push(name);
-                    self.save_config();
+                    GearFilterMessage::MinLevelChanged(content) => filter.min_level = content,
+                    GearFilterMessage::MaxLevelChanged(content) => filter.max_level = content,
+                    GearFilterMessage::ClassSelected(class) => filter.class = class,
+                    GearFilterMessage::Reset => *filter = GearFilter::new(&self.gear_list),
                 }
-                GearMessage::AddBoots => {
-                    self.gear.boots_selections.push(None);
+                self.gear.apply_filter(&self.gear_list, &self.gear_filter, self.config.player.class);
+            }
+            ConfigMessage::Preset(preset_message) => match preset_message {
+                PresetMessage::Selected(preset) => {
+                    self.preset_name = preset.name.clone();
+                    self.selected_preset = Some(preset);
+                    self.preset_status = None;
                 }
-                GearMessage::RingsSelected(idx, name) => {
-                    if let Some(selection) =
-                        self.gear.rings_selections.get_mut(idx)
-                    {
-                        *selection = Some(name.clone());
+                PresetMessage::Apply(mode) => {
+                    let Some(preset) = &self.selected_preset else {
+                        return Task::none();
+                    };
+                    match preset.apply(&self.config, mode) {
+                        Ok(config) => {
+                            self.config = config;
+                            // Stale toggles and half-typed values would hide what the preset set
+                            self.enabled_thresholds.clear();
+                            self.disabled_thresholds.clear();
+                            self.drafts.clear();
+                            self.preset_status = Some(Ok(format!("Applied \"{}\"", preset.name)));
+                            self.save_config();
+                        }
+                        Err(e) => self.preset_status = Some(Err(e)),
                     }
-                    self.config.items.rings.push(name);
-                    self.save_config();
                 }
-                GearMessage::AddRings => {
-                    self.gear.rings_selections.push(None);
+                PresetMessage::NameChanged(content) => {
+                    self.preset_name = content;
                 }
-                GearMessage::BraceletsSelected(idx, name) => {
-                    if let Some(selection) =
-                        self.gear.bracelets_selections.get_mut(idx)
-                    {
-                        *selection = Some(name.clone());
+                PresetMessage::Save => {
+                    let saved = Preset::from_config(self.preset_name.trim(), &self.config)
+                        .and_then(|mut preset| {
+                            preset.path = Some(presets::save_preset(&preset)?);
+                            Ok(preset)
+                        });
+                    match saved {
+                        Ok(preset) => {
+                            self.presets.retain(|existing| existing.path != preset.path);
+                            self.preset_status = Some(Ok(format!("Saved \"{}\"", preset.name)));
+                            self.presets.push(preset.clone());
+                            self.selected_preset = Some(preset);
+                        }
+                        Err(e) => self.preset_status = Some(Err(e)),
                     }
-                    self.config.items.bracelets.push(name);
-                    self.save_config();
                 }
-                GearMessage::AddBracelets => {
-                    self.gear.bracelets_selections.push(None);
-                }
-                GearMessage::NecklacesSelected(idx, name) => {
-                    if let Some(selection) =
-                        self.gear.necklaces_selections.get_mut(idx)
-                    {
-                        *selection = Some(name.clone());
+                PresetMessage::Delete => {
+                    let Some(preset) = self.selected_preset.take() else {
+                        return Task::none();
+                    };
+                    // Built-in presets have no file and can't be deleted
+                    let Some(path) = &preset.path else {
+                        self.selected_preset = Some(preset);
+                        return Task::none();
+                    };
+                    match std::fs::remove_file(path) {
+                        Ok(()) => {
+                            self.presets.retain(|existing| existing != &preset);
+                            self.preset_status = Some(Ok(format!("Deleted \"{}\"", preset.name)));
+                        }
+                        Err(e) => {
+                            self.preset_status = Some(Err(e.to_string()));
+                            self.selected_preset = Some(preset);
+                        }
                     }
-                    self.config.items.necklaces.push(name);
-                    self.save_config();
                 }
-                GearMessage::AddNecklaces => {
-                    self.gear.necklaces_selections.push(None);
+            },
+            ConfigMessage::Bundle(bundle_message) => match bundle_message {
+                BundleMessage::PathChanged(content) => {
+                    self.bundle_path = content;
                 }
-                GearMessage::WeaponSelected(name) => {
-                    self.gear.selected_weapon = Some(name);
-                    self.save_config();
+                BundleMessage::IncludeItemsToggled(include) => {
+                    self.bundle_include_items = include;
+                }
+                BundleMessage::Edit(action) => {
+                    self.bundle_text.perform(action);
                 }
-                GearMessage::RemoveHelmet(idx) => {
-                    self.gear.helmet_selections.remove(idx);
-                    let helmet = self.config.items.helmets.get(idx).cloned();
-                    if let Some(helmet) = helmet {
-                        self.config
-                            .items
-                            .helmets
-                            .retain(|x| x != &helmet);
+                BundleMessage::ExportFile => {
+                    let bundle = Bundle::new(&self.config, &self.gear_list, self.bundle_include_items);
+                    self.bundle_status = Some(
+                        bundle
+                            .save(&self.bundle_path)
+                            .map(|_| format!("Exported to {}", self.bundle_path)),
+                    );
+                }
+                BundleMessage::ExportString => {
+                    let bundle = Bundle::new(&self.config, &self.gear_list, self.bundle_include_items);
+                    match bundle.to_compact() {
+                        Ok(encoded) => {
+                            self.bundle_text = text_editor::Content::with_text(&encoded);
+                            self.bundle_status = Some(Ok("Config string copied to the clipboard".to_string()));
+                            return clipboard::write(encoded);
+                        }
+                        Err(e) => self.bundle_status = Some(Err(e)),
                     }
-                    self.save_config();
                 }
-                GearMessage::RemoveChestplate(idx) => {
-                    self.gear.chestplate_selections.remove(idx);
-                    let chestplate = self
-                        .config
-                        .items
-                        .chest_plates
-                        .get(idx)
-                        .cloned();
-                    if let Some(chestplate) = chestplate {
-                        self.config
-                            .items
-                            .chest_plates
-                            .retain(|x| x != &chestplate);
+                BundleMessage::ImportFile => match Bundle::load(&self.bundle_path) {
+                    Ok(bundle) => {
+                        self.pending_import = Some(bundle.preview(&self.config));
+                        self.bundle_status = None;
                     }
+                    Err(e) => self.bundle_status = Some(Err(e)),
+                },
+                BundleMessage::ImportString => match Bundle::parse(&self.bundle_text.text()) {
+                    Ok(bundle) => {
+                        self.pending_import = Some(bundle.preview(&self.config));
+                        self.bundle_status = None;
+                    }
+                    Err(e) => self.bundle_status = Some(Err(e)),
+                },
+                BundleMessage::Apply => {
+                    let Some(pending) = self.pending_import.take() else {
+                        return Task::none();
+                    };
+
+                    let mut added = 0;
+                    for gear in pending.bundle.items {
+                        if self.gear_list.find(&gear.name).is_none() {
+                            self.gear_list.items.push(gear);
+                            added += 1;
+                        }
+                    }
+                    if added > 0 {
+                        self.fill_pools = FillPools::new(&self.gear_list);
+                    }
+
+                    self.config = pending.bundle.config;
+                    self.gear = GearSelections::new(&self.gear_list, &self.config.items, &self.gear_filter, self.config.player.class);
+                    self.enabled_thresholds.clear();
+                    self.disabled_thresholds.clear();
+                    self.drafts.clear();
+                    self.bundle_status = Some(Ok(if added > 0 {
+                        format!(
+                            "Imported config and {} item(s); the items last until restart and aren't written to items.json",
+                            added
+                        )
+                    } else {
+                        "Imported config".to_string()
+                    }));
                     self.save_config();
                 }
-                GearMessage::RemoveLeggings(idx) => {
-                    self.gear.leggings_selections.remove(idx);
-                    let legging = self.config.items.leggings.get(idx).cloned();
-                    if let Some(legging) = legging {
-                        self.config
-                            .items
-                            .leggings
-                            .retain(|x| x != &legging);
+                BundleMessage::Discard => {
+                    self.pending_import = None;
+                }
+            },
+            ConfigMessage::InspectItem(name) => {
+                self.inspected_item = name;
+                return Task::none();
+            }
+            ConfigMessage::SkillPoints(skill_point_message) => {
+                match skill_point_message {
+                    SkillPointMessage::Selected(idx, name) => {
+                        if let Some(item) = self.skill_point_items.get_mut(idx) {
+                            *item = Some(name);
+                        }
                     }
-                    self.save_config();
+                    SkillPointMessage::Clear => self.skill_point_items = Default::default(),
+                }
+                self.refresh_skill_points();
+                return Task::none();
+            }
+            ConfigMessage::DismissChangelog => {
+                self.item_changelog = None;
+                return Task::none();
+            }
+            ConfigMessage::RawModeToggled(enabled) => {
+                self.raw_mode = enabled;
+                // Forces the editor to be filled from the config on the next sync
+                self.raw_synced.clear();
+            }
+            ConfigMessage::RawEdit(action) => {
+                let is_edit = action.is_edit();
+                self.raw_text.perform(action);
+                if !is_edit {
+                    return Task::none();
                 }
-                GearMessage::RemoveBoots(idx) => {
-                    self.gear.boots_selections.remove(idx);
-                    let boot = self.config.items.boots.get(idx).cloned();
-                    if let Some(boot) = boot {
-                        self.config
-                            .items
-                            .boots
-                            .retain(|x| x != &boot);
+
+                match migrations::parse_config(&self.raw_text.text()) {
+                    Ok((config, _)) => {
+                        self.raw_synced = toml::to_string(&config).unwrap_or_default();
+                        self.raw_error = None;
+                        self.gear = GearSelections::new(&self.gear_list, &config.items, &self.gear_filter, config.player.class);
+                        self.config = config;
+                        self.drafts.clear();
+                        self.save_config();
                     }
-                    self.save_config();
+                    Err(e) => self.raw_error = Some(e),
                 }
-                GearMessage::RemoveRings(idx) => {
-                    self.gear.rings_selections.remove(idx);
-                    let ring = self.config.items.rings.get(idx).cloned();
-                    if let Some(ring) = ring {
-                        self.config
-                            .items
-                            .rings
-                            .retain(|x| x != &ring);
+            }
+            ConfigMessage::LoadError(load_error_message) => match load_error_message {
+                LoadErrorMessage::Retry => self.reload_config(),
+                LoadErrorMessage::StartFresh => self.start_fresh(),
+                LoadErrorMessage::Dismiss => self.load_error_dismissed = true,
+            },
+            ConfigMessage::Compare(compare_message) => match compare_message {
+                CompareMessage::PathChanged(content) => {
+                    self.compare_path = content;
+                }
+                CompareMessage::Load => match build_config::read_config(&self.compare_path) {
+                    Ok(other) => {
+                        self.comparison = Some(Comparison {
+                            diff: config_diff::diff(&self.config, &other),
+                            path: self.compare_path.clone(),
+                            other,
+                            pending_write: None,
+                        });
+                        self.compare_status = None;
                     }
-                    self.save_config();
+                    Err(e) => self.compare_status = Some(Err(format!("{}: {}", self.compare_path, e))),
+                },
+                CompareMessage::Close => {
+                    self.comparison = None;
+                    self.compare_status = None;
                 }
-                GearMessage::RemoveBracelets(idx) => {
-                    self.gear.bracelets_selections.remove(idx);
-                    let bracelet = self
-                        .config
-                        .items
-                        .bracelets
-                        .get(idx)
-                        .cloned();
-                    if let Some(bracelet) = bracelet {
-                        self.config
-                            .items
-                            .bracelets
-                            .retain(|x| x != &bracelet);
+                CompareMessage::TakeLeft(path) => {
+                    if let Some(comparison) = &mut self.comparison {
+                        comparison.pending_write = Some(path);
                     }
-                    self.save_config();
                 }
-                GearMessage::RemoveNecklaces(idx) => {
-                    self.gear.necklaces_selections.remove(idx);
-                    let necklace = self
-                        .config
-                        .items
-                        .necklaces
-                        .get(idx)
-                        .cloned();
-                    if let Some(necklace) = necklace {
-                        self.config
-                            .items
-                            .necklaces
-                            .retain(|x| x != &necklace);
+                CompareMessage::CancelTakeLeft => {
+                    if let Some(comparison) = &mut self.comparison {
+                        comparison.pending_write = None;
                     }
-                    self.save_config();
+                }
+                CompareMessage::ConfirmTakeLeft => {
+                    let Some(comparison) = &mut self.comparison else {
+                        return Task::none();
+                    };
+                    let Some(path) = comparison.pending_write.take() else {
+                        return Task::none();
+                    };
+                    let value = config_diff::flatten(&self.config).remove(&path);
+                    let result = config_diff::set_field(&comparison.other, &path, value).and_then(|other| {
+                        other.save_config(&comparison.path)?;
+                        Ok(other)
+                    });
+                    match result {
+                        Ok(other) => {
+                            comparison.other = other;
+                            self.compare_status = Some(Ok(format!("Wrote {} to {}", path, comparison.path)));
+                        }
+                        Err(e) => self.compare_status = Some(Err(e)),
+                    }
+                    self.refresh_comparison();
+                    return Task::none();
+                }
+                CompareMessage::TakeRight(path) => {
+                    let Some(comparison) = &self.comparison else {
+                        return Task::none();
+                    };
+                    let value = config_diff::flatten(&comparison.other).remove(&path);
+                    match config_diff::set_field(&self.config, &path, value) {
+                        Ok(config) => {
+                            self.config = config;
+                            if path.starts_with("items.") {
+                                self.gear = GearSelections::new(&self.gear_list, &self.config.items, &self.gear_filter, self.config.player.class);
+                            }
+                            self.drafts.remove(&path);
+                            self.compare_status = Some(Ok(format!("Took {} from {}", path, comparison.path)));
+                            self.save_config();
+                        }
+                        Err(e) => self.compare_status = Some(Err(e)),
+                    }
+                    self.refresh_comparison();
                 }
             },
             ConfigMessage::Player(player_message) => match player_message {
                 PlayerMessage::LevelChanged(content) => {
-                    if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                        self.config.player.lvl = content.parse().unwrap_or(1);
+                    if let Some(lvl) = self.parse_draft("player.lvl", content) {
+                        self.config.player.lvl = lvl;
                         self.save_config();
                     }
                 }
                 PlayerMessage::AvailablePointChanged(content) => {
-                    if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                        self.config.player.available_point =
-                            content.parse().unwrap_or(200);
+                    if let Some(points) = self.parse_draft("player.available_point", content) {
+                        self.config.player.available_point = points;
                         self.save_config();
                     }
                 }
                 PlayerMessage::BaseHpChanged(content) => {
-                    if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                        self.config.player.base_hp =
-                            content.parse().unwrap_or(500);
-                        self.save_config();
-                    }
-                }
-            },
-            ConfigMessage::ThresholdFirst(threshold_first_message) => {
-                match threshold_first_message {
-                    ThresholdFirstMessage::HpChanged(content) => {
-                        if content.is_empty() {
-                            self.config
-                                .threshold_first
-                                .as_mut()
-                                .unwrap()
-                                .min_hp = None;
-                        } else if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                            self.config
-                                .threshold_first
-                                .as_mut()
-                                .unwrap()
-                                .min_hp = Some(content.parse().unwrap_or(0));
-                        }
+                    if let Some(base_hp) = self.parse_draft("player.base_hp", content) {
+                        self.config.player.base_hp = base_hp;
                         self.save_config();
                     }
                 }
-            }
-            ConfigMessage::ThresholdSecond(threshold_second_message) => {
-                if let Some(threshold) = self.config.threshold_second.as_mut() {
-                    match threshold_second_message {
-                        ThresholdSecondMessage::HprRawChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_hpr_raw = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::HprPctChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_hpr_pct = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::MrChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_mr = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::LsChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_ls = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::MsChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_ms = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::SpdChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_spd = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::SdRawChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_sd_raw = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::SdPctChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_sd_pct = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::HprChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_hpr = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdSecondMessage::ExpBonusChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_exp_bonus = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                    }
+                PlayerMessage::ClassSelected(class) => {
+                    self.config.player.class = class;
+                    self.gear.apply_filter(&self.gear_list, &self.gear_filter, class);
                     self.save_config();
                 }
-            }
-            ConfigMessage::ThresholdThird(threshold_third_message) => {
-                if let Some(threshold) = self.config.threshold_third.as_mut() {
-                    match threshold_third_message {
-                        ThresholdThirdMessage::EarthDefChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_earth_defense = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdThirdMessage::ThunderDefChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_thunder_defense = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdThirdMessage::WaterDefChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_water_defense = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdThirdMessage::FireDefChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_fire_defense = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdThirdMessage::AirDefChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_air_defense = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
+            },
+            ConfigMessage::ThresholdToggled(section, enabled) => {
+                if enabled {
+                    self.enabled_thresholds.insert(section);
+                    if let Some(table) = self.disabled_thresholds.remove(&section) {
+                        section.set_table(&mut self.config, Some(table)).unwrap_or_default();
+                        self.save_config();
                     }
-                    self.save_config();
-                }
-            }
-            ConfigMessage::ThresholdFourth(threshold_fourth_message) => {
-                if let Some(threshold) = self.config.threshold_fourth.as_mut() {
-                    match threshold_fourth_message {
-                        ThresholdFourthMessage::NeutralDamPctChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_neutral_dam_pct = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFourthMessage::EarthDamPctChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_earth_dam_pct = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFourthMessage::ThunderDamPctChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_thunder_dam_pct = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFourthMessage::WaterDamPctChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_water_dam_pct = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFourthMessage::FireDamPctChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_fire_dam_pct = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFourthMessage::AirDamPctChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_air_dam_pct = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
+                } else {
+                    self.enabled_thresholds.remove(&section);
+                    if let Some(table) = section.take(&mut self.config) {
+                        self.disabled_thresholds.insert(section, table);
                     }
+                    // Half-typed values of the hidden fields can't be fixed anymore
+                    let prefix = format!("{}.", section.key());
+                    self.drafts.retain(|path, _| !path.starts_with(&prefix));
                     self.save_config();
                 }
             }
-            ConfigMessage::ThresholdFifth(threshold_fifth_message) => {
-                if let Some(threshold) = self.config.threshold_fifth.as_mut() {
-                    match threshold_fifth_message {
-                        ThresholdFifthMessage::EarthPointChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_earth_point = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFifthMessage::ThunderPointChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_thunder_point = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFifthMessage::WaterPointChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_water_point = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFifthMessage::FirePointChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_fire_point = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFifthMessage::AirPointChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_air_point = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
-                        ThresholdFifthMessage::EhpChanged(content) => {
-                            if content.chars().all(|c| c.is_ascii_digit() || c == '-') {
-                                threshold.min_ehp = match content.is_empty() {
-                                    true => None,
-                                    false => Some(content.parse().unwrap_or(0)),
-                                };
-                            }
-                        }
+            ConfigMessage::ThresholdChanged(section, key, content) => {
+                let Some(field) = thresholds::field(section, key) else {
+                    return Task::none();
+                };
+                // Keep the section open even if this edit clears its last field
+                self.enabled_thresholds.insert(section);
+
+                let value = match content.parse::<i64>() {
+                    _ if content.is_empty() => None,
+                    Ok(value) if field.numeric.fits(value) => Some(value),
+                    // Kept as typed, validation reports it until it's fixed
+                    _ => {
+                        self.drafts.insert(field.path(), content);
+                        self.validate();
+                        return Task::none();
                     }
-                    self.save_config();
-                }
+                };
+                self.drafts.remove(&field.path());
+
+                field.set(&mut self.config, value).unwrap_or_default();
+                self.save_config();
             }
             ConfigMessage::Hppeng(hppeng_message) => {
                 match hppeng_message {
@@ -497,13 +481,101 @@ impl ConfigFile {
                         self.config.hppeng.log_db_errors = value;
                     }
                     HppengMessage::DbRetryCountChanged(content) => {
-                        self.config.hppeng.db_retry_count =
-                            content.parse().unwrap_or(3);
+                        if let Some(count) = self.parse_draft("hppeng.db_retry_count", content) {
+                            self.config.hppeng.db_retry_count = count;
+                        }
                     }
                 }
                 self.save_config();
             }
+            ConfigMessage::Api(api_message) => {
+                match api_message {
+                    ApiMessage::UrlChanged(content) => {
+                        self.config.api.get_or_insert_with(Default::default).url = content;
+                    }
+                    ApiMessage::VersionChanged(content) => {
+                        self.config.api.get_or_insert_with(Default::default).version = content;
+                    }
+                    ApiMessage::ModuleChanged(content) => {
+                        self.config.api.get_or_insert_with(Default::default).module = content;
+                    }
+                    ApiMessage::QueryChanged(content) => {
+                        self.config.api.get_or_insert_with(Default::default).query = content;
+                    }
+                    ApiMessage::TestConnection => {
+                        let Some(endpoint) = self.config.api.as_ref().map(Api::endpoint) else {
+                            self.api_status = Some(Err("Set the API URL first".to_string()));
+                            return Task::none();
+                        };
+                        self.api_testing = true;
+                        self.api_status = None;
+                        return Task::perform(api::test_connection(endpoint), |result| {
+                            Message::Config(ConfigMessage::Api(ApiMessage::ConnectionTested(result)))
+                        });
+                    }
+                    ApiMessage::ConnectionTested(result) => {
+                        self.api_testing = false;
+                        self.api_status = Some(result);
+                        return Task::none();
+                    }
+                    ApiMessage::UpdateItems => {
+                        let Some(endpoint) = self.config.api.as_ref().map(Api::endpoint) else {
+                            self.items_update_status = Some(Err("Set the API URL first".to_string()));
+                            return Task::none();
+                        };
+                        self.items_updating = true;
+                        self.items_update_status = None;
+                        return Task::perform(
+                            api::update_items(HttpClient, endpoint, ITEMS_PATH.into()),
+                            |result| Message::Config(ConfigMessage::Api(ApiMessage::ItemsUpdated(result))),
+                        );
+                    }
+                    ApiMessage::ItemsUpdated(result) => {
+                        self.items_updating = false;
+                        let updated = result.is_ok();
+                        self.items_update_status = Some(result);
+                        if !updated {
+                            return Task::none();
+                        }
+                        // Reload through the cache, which sees the new hash and rebuilds
+                        self.items_loading = true;
+                        return Task::perform(
+                            item_db::load(ITEMS_PATH.into(), CACHE_PATH.into()),
+                            Message::ItemsLoaded,
+                        );
+                    }
+                }
+                // An emptied section is dropped so it doesn't end up in the TOML
+                if self.config.api.as_ref().is_some_and(Api::is_empty) {
+                    self.config.api = None;
+                }
+                self.save_config();
+            }
+            ConfigMessage::CombosPerSecondChanged(content) => {
+                if content.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                    self.combos_per_second = content;
+                }
+                return Task::none();
+            }
         }
+        self.validate();
+        self.refresh_comparison();
+        self.sync_raw_text();
         Task::none()
     }
+
+    /// Parses numeric input for the field at `key`. Text that doesn't parse is kept as a
+    /// draft instead of replacing the value, so validation can point it out.
+    fn parse_draft<T: std::str::FromStr>(&mut self, key: &str, content: String) -> Option<T> {
+        match content.trim().parse() {
+            Ok(value) => {
+                self.drafts.remove(key);
+                Some(value)
+            }
+            Err(_) => {
+                self.drafts.insert(key.to_string(), content);
+                None
+            }
+        }
+    }
 } 
\ No newline at end of file
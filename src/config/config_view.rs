@@ -1,17 +1,32 @@
 use iced::{
     alignment::{Horizontal, Vertical},
-    Length,
+    Color, Element, Font, Length, Theme,
 };
 use iced_widget::{
-    button, checkbox, column, combo_box, container, row, scrollable, text, text_input, Container,
+    button, checkbox, column, combo_box, container, pick_list, row, scrollable, text, text_editor,
+    text_input, Container, Space,
 };
 
-use crate::{build_config::{ThresholdFirst, ThresholdSecond, ThresholdThird, ThresholdFourth, ThresholdFifth}, messages::Message, ConfigMessage, GearMessage, HppengMessage, PlayerMessage, ThresholdFifthMessage, ThresholdFirstMessage, ThresholdFourthMessage, ThresholdSecondMessage, ThresholdThirdMessage};
-use super::ConfigFile;
+use crate::{build_config::Api, messages::Message, ApiMessage, BulkImportMessage, ConfigMessage, FillPoolsMessage, GearFilterMessage, GearMessage, HppengMessage, IllegalCombinationMessage, ItemCompareMessage, PlayerMessage, PresetMessage, SkillPointMessage, BundleMessage, CompareMessage, LoadErrorMessage};
+use super::estimate::{self, LARGE_SEARCH, LONG_RUNTIME};
+use super::item_view;
+use super::presets::PresetMode;
+use super::skill_points::{EQUIP_SLOTS, SKILLS};
+use super::thresholds::ThresholdField;
+use super::config_diff::FieldDiff;
+use super::{Class, Comparison, ConfigFile, GearSlot, ThresholdSection, CONFIG_PATH};
 use super::style::*;
+use crate::item_changelog::ItemChange;
+
+/// Items listed per changelog section, besides the pooled ones.
+const CHANGELOG_LIMIT: usize = 50;
 
 impl ConfigFile {
     pub fn view(&self) -> Container<'_, Message> {
+        if let Some(comparison) = &self.comparison {
+            return self.comparison_view(comparison);
+        }
+
         let content = column![
                 text("Edit Configuration File").size(HEADER),
                 text("The configuration will be saved automatically when you edit.").size(SUBHEAD),
@@ -20,9 +35,39 @@ impl ConfigFile {
                 if let Some(error_message) = &self.error_message {
                     text(error_message).size(SUBHEAD).color(ERROR)
                 } else {
-                    text("").size(SUBHEAD)
+                    text(self.items_status()).size(SUBHEAD).color_maybe(self.items_cache_error.as_ref().map(|_| WARNING))
                 },
                 text("Using this tab when there's a red error message above is undocumented and unexpected behavior, here be dragons!").size(SUBHEAD).color(WARNING),
+                self.item_changelog(),
+                checkbox("Edit as TOML", self.raw_mode)
+                    .on_toggle(|enabled| Message::Config(ConfigMessage::RawModeToggled(enabled)))
+                    .size(TEXT)
+                    .spacing(SPACE),
+                // Sharing
+                text("Import / Export").size(SUBHEAD),
+                text("Export the config to a file or a compact string to share, or import one. Imports show what would change before anything is applied.").size(TEXT),
+                self.bundle_form(),
+                self.pending_import(),
+                // Compare
+                text("Compare").size(SUBHEAD),
+                text("Compare the current config against another config.toml and copy values between them.").size(TEXT),
+                row![
+                    text("Other Config:").width(Length::Fixed(LABEL_WIDTH)),
+                    text_input("Enter file path...", &self.compare_path)
+                        .on_input(|input| Message::Config(ConfigMessage::Compare(CompareMessage::PathChanged(input))))
+                        .size(TEXT)
+                        .padding(INPUT_PAD),
+                    button("Compare")
+                        .on_press(Message::Config(ConfigMessage::Compare(CompareMessage::Load)))
+                        .padding(BTN_PAD),
+                ]
+                .spacing(SPACE)
+                .align_y(Vertical::Center),
+                self.compare_status(),
+                // Presets
+                text("Presets").size(SUBHEAD),
+                text("Presets hold player and threshold settings. Merge only overwrites the values a preset sets; Replace also clears thresholds it doesn't set.").size(TEXT),
+                self.presets(),
                 text("Player Settings").size(SUBHEAD),
                 // Player settings
                 container(
@@ -31,35 +76,55 @@ impl ConfigFile {
                             text("Player Level:").width(Length::Fixed(LABEL_WIDTH)),
                             text_input(
                                 "Enter level (1-106)...",
-                                &self.config.player.lvl.to_string()
+                                &self.draft_or("player.lvl", self.config.player.lvl)
                             )
                             .on_input(|input| Message::Config(ConfigMessage::Player(PlayerMessage::LevelChanged(input))))
                             .size(TEXT)
                             .padding(INPUT_PAD)
                             .width(Length::Fill),
+                            self.field_error("player.lvl"),
                         ],
                         row![
                             text("Available Points:").width(Length::Fixed(LABEL_WIDTH)),
                             text_input(
                                 "Enter points...", 
-                                &self.config.player.available_point.to_string()
+                                &self.draft_or("player.available_point", self.config.player.available_point)
                             )
                             .on_input(|input| Message::Config(ConfigMessage::Player(PlayerMessage::AvailablePointChanged(input))))
                             .size(TEXT)
                             .padding(INPUT_PAD)
                             .width(Length::Fill),
+                            self.field_error("player.available_point"),
                         ],
                         row![
                             text("Base HP:").width(Length::Fixed(LABEL_WIDTH)),
                             text_input(
                                 "Enter base HP...",
-                                &self.config.player.base_hp.to_string()
+                                &self.draft_or("player.base_hp", self.config.player.base_hp)
                             )
                             .on_input(|input| Message::Config(ConfigMessage::Player(PlayerMessage::BaseHpChanged(input))))
                             .size(TEXT)
                             .padding(INPUT_PAD)
                             .width(Length::Fill),
+                            self.field_error("player.base_hp"),
                         ],
+                        row![
+                            text("Class:").width(Length::Fixed(LABEL_WIDTH)),
+                            pick_list(&Class::ALL[..], self.config.player.class, |class| {
+                                Message::Config(ConfigMessage::Player(PlayerMessage::ClassSelected(Some(class))))
+                            })
+                            .placeholder("Any class")
+                            .padding(INPUT_PAD),
+                            button("Any")
+                                .on_press_maybe(self.config.player.class.map(|_| {
+                                    Message::Config(ConfigMessage::Player(PlayerMessage::ClassSelected(None)))
+                                }))
+                                .style(button::secondary)
+                                .padding(BTN_PAD),
+                            self.field_error("player.class"),
+                        ]
+                        .spacing(SPACE)
+                        .align_y(Vertical::Center),
                     ]
                 )
                 .width(Length::Fill)
@@ -148,596 +213,263 @@ impl ConfigFile {
                             text("DB Retry Count:").width(Length::Fixed(LABEL_WIDTH)),
                             text_input(
                                 "Enter retry count...",
-                                &self.config.hppeng.db_retry_count.to_string()
+                                &self.draft_or("hppeng.db_retry_count", self.config.hppeng.db_retry_count)
                             )
                             .on_input(|input| Message::Config(ConfigMessage::Hppeng(HppengMessage::DbRetryCountChanged(input))))
                             .size(TEXT)
                             .padding(INPUT_PAD)
                             .width(Length::Fill),
+                            self.field_error("hppeng.db_retry_count"),
                         ],
                     ]
                 )
                 .width(Length::Fill)
                 .height(Length::Shrink)
                 .padding(SPACE),
-                // Threshold First settings
-                text("Threshold First Settings").size(SUBHEAD),
-                container(
-                    column![
-                        row![
-                    text("Min HP:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Health Points...",
-                        &self.config.threshold_first.as_ref().unwrap_or(&ThresholdFirst::default()).min_hp
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdFirst(ThresholdFirstMessage::HpChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                        ],
-                    ]
-                )
-                .width(Length::Fill)
-                .height(Length::Shrink)
-                .padding(SPACE),
-                // Threshold Second settings
-                text("Threshold Second Settings").size(SUBHEAD),
-                container(
-                    column![
-                        row![
-                    text("Min Health Regen Raw:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Health Regen raw...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_hpr_raw
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::HprRawChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Health Regen %:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Health Regen percentage...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_hpr_pct
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::HprPctChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Mana Regen:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Mana Regen...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_mr
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::MrChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Life Steal:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Life Steal...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_ls
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::LsChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Mana Steal:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Mana Steal...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_ms
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::MsChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Speed:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Speed...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_spd
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::SpdChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Spell Damage Raw:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Spell Damage raw...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_sd_raw
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::SdRawChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Spell Damage %:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Spell Damage percentage...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_sd_pct
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::SdPctChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Health Regen:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Health Regen...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_hpr
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::HprChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Experience Bonus:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter minimum Experience bonus...",
-                        &self.config.threshold_second.as_ref().unwrap_or(&ThresholdSecond::default()).min_exp_bonus
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdSecond(ThresholdSecondMessage::ExpBonusChanged(input))))
-                    .size(TEXT)
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                        ],
-                    ]
-                )
-                .width(Length::Fill)
-                .height(Length::Shrink)
-                .padding(SPACE),
-                // Threshold Third settings
-                text("Threshold Third Settings").size(SUBHEAD),
+                // API settings
+                text("API Settings").size(SUBHEAD),
                 container(
                     column![
                         row![
-                    text("Min Earth Defense:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min earth defense...",
-                        &self.config.threshold_third.as_ref().unwrap_or(&ThresholdThird::default()).min_earth_defense
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdThird(ThresholdThirdMessage::EarthDefChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Thunder Defense:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min thunder defense...",
-                        &self.config.threshold_third.as_ref().unwrap_or(&ThresholdThird::default()).min_thunder_defense
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdThird(ThresholdThirdMessage::ThunderDefChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Water Defense:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min water defense...",
-                        &self.config.threshold_third.as_ref().unwrap_or(&ThresholdThird::default()).min_water_defense
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdThird(ThresholdThirdMessage::WaterDefChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Fire Defense:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min fire defense...",
-                        &self.config.threshold_third.as_ref().unwrap_or(&ThresholdThird::default()).min_fire_defense
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdThird(ThresholdThirdMessage::FireDefChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Air Defense:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min air defense...",
-                        &self.config.threshold_third.as_ref().unwrap_or(&ThresholdThird::default()).min_air_defense
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdThird(ThresholdThirdMessage::AirDefChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                    ]
-                )
-                .width(Length::Fill)
-                .height(Length::Shrink)
-                .padding(SPACE),
-                // Threshold Fourth settings
-                text("Threshold Fourth Settings").size(SUBHEAD),
-                container(
-                    column![
-                        row![
-                    text("Min Neutral Damage %:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min neutral damage %...",
-                        &self.config.threshold_fourth.as_ref().unwrap_or(&ThresholdFourth::default()).min_neutral_dam_pct
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdFourth(ThresholdFourthMessage::NeutralDamPctChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Earth Damage %:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min earth damage %...",
-                        &self.config.threshold_fourth.as_ref().unwrap_or(&ThresholdFourth::default()).min_earth_dam_pct
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdFourth(ThresholdFourthMessage::EarthDamPctChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Thunder Damage %:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min thunder damage %...",
-                        &self.config.threshold_fourth.as_ref().unwrap_or(&ThresholdFourth::default()).min_thunder_dam_pct
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdFourth(ThresholdFourthMessage::ThunderDamPctChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Water Damage %:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min water damage %...",
-                        &self.config.threshold_fourth.as_ref().unwrap_or(&ThresholdFourth::default()).min_water_dam_pct
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdFourth(ThresholdFourthMessage::WaterDamPctChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Fire Damage %:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min fire damage %...",
-                        &self.config.threshold_fourth.as_ref().unwrap_or(&ThresholdFourth::default()).min_fire_dam_pct
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdFourth(ThresholdFourthMessage::FireDamPctChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                row![
-                    text("Min Air Damage %:").width(Length::Fixed(LABEL_WIDTH)),
-                    text_input(
-                        "Enter min air damage %...",
-                        &self.config.threshold_fourth.as_ref().unwrap_or(&ThresholdFourth::default()).min_air_dam_pct
-                            .map(|v| v.to_string())
-                            .unwrap_or_default()
-                    )
-                    .on_input(|input| Message::Config(ConfigMessage::ThresholdFourth(ThresholdFourthMessage::AirDamPctChanged(input))))
-                    .padding(INPUT_PAD)
-                    .width(Length::Fill),
-                ],
-                    ]
-                ),
-                // Threshold Fifth settings
-                text("Threshold Fifth Settings").size(SUBHEAD),
-                container(
-                    column![
-                        row![
-                            text("Min Earth Point:").width(Length::Fixed(LABEL_WIDTH)),
+                            text("URL:").width(Length::Fixed(LABEL_WIDTH)),
                             text_input(
-                                "Enter min earth point...",
-                                &self.config.threshold_fifth.as_ref().unwrap_or(&ThresholdFifth::default()).min_earth_point
-                                    .map(|v| v.to_string())
-                                    .unwrap_or_default()
+                                "Enter API URL...",
+                                &self.config.api.as_ref().unwrap_or(&Api::default()).url
                             )
-                            .on_input(|input| Message::Config(ConfigMessage::ThresholdFifth(ThresholdFifthMessage::EarthPointChanged(input))))
+                            .on_input(|input| Message::Config(ConfigMessage::Api(ApiMessage::UrlChanged(input))))
+                            .size(TEXT)
                             .padding(INPUT_PAD)
                             .width(Length::Fill),
                         ],
                         row![
-                            text("Min Thunder Point:").width(Length::Fixed(LABEL_WIDTH)),
+                            text("Version:").width(Length::Fixed(LABEL_WIDTH)),
                             text_input(
-                                "Enter min thunder point...",
-                                &self.config.threshold_fifth.as_ref().unwrap_or(&ThresholdFifth::default()).min_thunder_point
-                                    .map(|v| v.to_string())
-                                    .unwrap_or_default()
+                                "Enter API version...",
+                                &self.config.api.as_ref().unwrap_or(&Api::default()).version
                             )
-                            .on_input(|input| Message::Config(ConfigMessage::ThresholdFifth(ThresholdFifthMessage::ThunderPointChanged(input))))
+                            .on_input(|input| Message::Config(ConfigMessage::Api(ApiMessage::VersionChanged(input))))
+                            .size(TEXT)
                             .padding(INPUT_PAD)
                             .width(Length::Fill),
                         ],
                         row![
-                            text("Min Water Point:").width(Length::Fixed(LABEL_WIDTH)),
+                            text("Module:").width(Length::Fixed(LABEL_WIDTH)),
                             text_input(
-                                "Enter min water point...",
-                                &self.config.threshold_fifth.as_ref().unwrap_or(&ThresholdFifth::default()).min_water_point
-                                    .map(|v| v.to_string())
-                                    .unwrap_or_default()
+                                "Enter API module...",
+                                &self.config.api.as_ref().unwrap_or(&Api::default()).module
                             )
-                            .on_input(|input| Message::Config(ConfigMessage::ThresholdFifth(ThresholdFifthMessage::WaterPointChanged(input))))
+                            .on_input(|input| Message::Config(ConfigMessage::Api(ApiMessage::ModuleChanged(input))))
+                            .size(TEXT)
                             .padding(INPUT_PAD)
                             .width(Length::Fill),
                         ],
                         row![
-                            text("Min Fire Point:").width(Length::Fixed(LABEL_WIDTH)),
+                            text("Query:").width(Length::Fixed(LABEL_WIDTH)),
                             text_input(
-                                "Enter min fire point...",
-                                &self.config.threshold_fifth.as_ref().unwrap_or(&ThresholdFifth::default()).min_fire_point
-                                    .map(|v| v.to_string())
-                                    .unwrap_or_default()
+                                "Enter query...",
+                                &self.config.api.as_ref().unwrap_or(&Api::default()).query
                             )
-                            .on_input(|input| Message::Config(ConfigMessage::ThresholdFifth(ThresholdFifthMessage::FirePointChanged(input))))
+                            .on_input(|input| Message::Config(ConfigMessage::Api(ApiMessage::QueryChanged(input))))
+                            .size(TEXT)
                             .padding(INPUT_PAD)
                             .width(Length::Fill),
                         ],
                         row![
-                            text("Min Air Point:").width(Length::Fixed(LABEL_WIDTH)),
-                            text_input(
-                                "Enter min air point...",
-                                &self.config.threshold_fifth.as_ref().unwrap_or(&ThresholdFifth::default()).min_air_point
-                                    .map(|v| v.to_string())
-                                    .unwrap_or_default()
-                            )
-                            .on_input(|input| Message::Config(ConfigMessage::ThresholdFifth(ThresholdFifthMessage::AirPointChanged(input))))
-                            .padding(INPUT_PAD)
-                            .width(Length::Fill),
-                        ],
+                            button("Test Connection")
+                                .on_press_maybe((!self.api_testing).then_some(Message::Config(ConfigMessage::Api(ApiMessage::TestConnection))))
+                                .padding(BTN_PAD),
+                            match &self.api_status {
+                                _ if self.api_testing => text("Testing...").size(TEXT),
+                                Some(Ok(status)) => text(format!("Response: {}", status)).size(TEXT),
+                                Some(Err(error)) => text(error).size(TEXT).color(ERROR),
+                                None => text(self.config.api.as_ref().map(Api::endpoint).unwrap_or_default()).size(TEXT),
+                            },
+                        ]
+                        .spacing(SPACE),
                         row![
-                            text("Min EHP:").width(Length::Fixed(LABEL_WIDTH)),
-                            text_input(
-                                "Enter min EHP...",
-                                &self.config.threshold_fifth.as_ref().unwrap_or(&ThresholdFifth::default()).min_ehp
-                                    .map(|v| v.to_string())
-                                    .unwrap_or_default()
-                            )
-                            .on_input(|input| Message::Config(ConfigMessage::ThresholdFifth(ThresholdFifthMessage::EhpChanged(input))))
-                            .padding(INPUT_PAD)
-                            .width(Length::Fill),
-                        ],
+                            button("Update Item Database")
+                                .on_press_maybe((!self.items_updating && !self.items_loading).then_some(Message::Config(ConfigMessage::Api(ApiMessage::UpdateItems))))
+                                .padding(BTN_PAD),
+                            match &self.items_update_status {
+                                _ if self.items_updating => text("Downloading items...").size(TEXT),
+                                Some(Ok(status)) => text(status).size(TEXT),
+                                Some(Err(error)) => text(error).size(TEXT).color(ERROR),
+                                None => text("Downloads items.json from the endpoint above and replaces the current one if it's valid.").size(TEXT),
+                            },
+                        ]
+                        .spacing(SPACE),
                     ]
                 )
                 .width(Length::Fill)
                 .height(Length::Shrink)
                 .padding(SPACE),
-                // Gear Selection - Helmets
-                column![
-                    text("Helmets:"),
-                    self.gear
-                        .helmet_selections
-                        .iter()
-                        .enumerate()
-                        .fold(column![].spacing(SPACE), |col, (idx, selection)| {
-                            col.push(
-                                row![
-                                    combo_box(
-                                        &self.gear.helmets,
-                                        "Select helmet...",
-                                        selection.as_ref(),
-                                        move |name| Message::Config(ConfigMessage::Gear(GearMessage::HelmetSelected(idx, name))),
-                                    ),
-                                    button("X")
-                                        .on_press(Message::Config(ConfigMessage::Gear(GearMessage::RemoveHelmet(idx))))
-                                        .padding(BTN_PAD),
-                                ]
-                                .spacing(SPACE)
-                            )
+                // Threshold settings, one row per field descriptor
+                ThresholdSection::ALL.into_iter().fold(column![].spacing(SPACE), |sections, section| {
+                    sections.push(self.threshold_header(section)).push_maybe(
+                        self.is_threshold_enabled(section).then(|| {
+                            container(section.fields().fold(column![], |col, field| {
+                                col.push(self.threshold_row(field))
+                            }))
+                            .width(Length::Fill)
+                            .height(Length::Shrink)
+                            .padding(SPACE)
                         }),
-                    button("Add Helmet").on_press(Message::Config(ConfigMessage::Gear(GearMessage::AddHelmet))),
-                ]
-                .spacing(SPACE),
-                // Gear Selection - Chestplates
-                column![
-                    text("Chestplates:"),
-                    self.gear
-                        .chestplate_selections
-                        .iter()
-                        .enumerate()
-                        .fold(column![].spacing(SPACE), |col, (idx, selection)| {
-                            col.push(
-                                row![
-                                    combo_box(
-                                        &self.gear.chestplates,
-                                        "Select chestplate...",
-                                        selection.as_ref(),
-                                        move |name| Message::Config(ConfigMessage::Gear(GearMessage::ChestplateSelected(idx, name))),
-                                    ),
-                                    button("X")
-                                        .on_press(Message::Config(ConfigMessage::Gear(GearMessage::RemoveChestplate(idx))))
-                                        .padding(BTN_PAD),
-                                ]
-                                .spacing(SPACE)
-                            )
-                        }),
-                    button("Add Chestplate").on_press(Message::Config(ConfigMessage::Gear(GearMessage::AddChestplate))),
-                ]
-                .spacing(SPACE),
-                // Gear Selection - Leggings
-                column![
-                    text("Leggings:"),
-                    self.gear
-                        .leggings_selections
-                        .iter()
-                        .enumerate()
-                        .fold(column![].spacing(SPACE), |col, (idx, selection)| {
-                            col.push(
-                                row![
-                                    combo_box(
-                                        &self.gear.leggings,
-                                        "Select leggings...",
-                                        selection.as_ref(),
-                                        move |name| Message::Config(ConfigMessage::Gear(GearMessage::LeggingsSelected(idx, name))),
-                                    ),
-                                    button("X")
-                                        .on_press(Message::Config(ConfigMessage::Gear(GearMessage::RemoveLeggings(idx))))
-                                        .padding(BTN_PAD),
-                                ]
-                                .spacing(SPACE)
-                            )
-                        }),
-                    button("Add Leggings").on_press(Message::Config(ConfigMessage::Gear(GearMessage::AddLeggings))),
-                ]
-                .spacing(SPACE),
-                // Gear Selection - Boots
-                column![
-                    text("Boots:"),
-                    self.gear
-                        .boots_selections
-                        .iter()
-                        .enumerate()
-                        .fold(column![].spacing(SPACE), |col, (idx, selection)| {
-                            col.push(
-                                row![
-                                    combo_box(
-                                        &self.gear.boots,
-                                        "Select boots...",
-                                        selection.as_ref(),
-                                        move |name| Message::Config(ConfigMessage::Gear(GearMessage::BootsSelected(idx, name))),
-                                    ),
-                                    button("X")
-                                        .on_press(Message::Config(ConfigMessage::Gear(GearMessage::RemoveBoots(idx))))
-                                        .padding(BTN_PAD),
-                                ]
-                                .spacing(SPACE)
-                            )
-                        }),
-                    button("Add Boots").on_press(Message::Config(ConfigMessage::Gear(GearMessage::AddBoots))),
-                ]
-                .spacing(SPACE),
-                // Gear Selection - Rings
-                column![
-                    text("Rings:"),
-                    self.gear
-                        .rings_selections
-                        .iter()
-                        .enumerate()
-                        .fold(column![].spacing(SPACE), |col, (idx, selection)| {
-                            col.push(
-                                row![
-                                    combo_box(
-                                        &self.gear.rings,
-                                        "Select ring...",
-                                        selection.as_ref(),
-                                        move |name| Message::Config(ConfigMessage::Gear(GearMessage::RingsSelected(idx, name))),
-                                    ),
-                                    button("X")
-                                        .on_press(Message::Config(ConfigMessage::Gear(GearMessage::RemoveRings(idx))))
-                                        .padding(BTN_PAD),
-                                ]
-                                .spacing(SPACE)
-                            )
-                        }),
-                    button("Add Ring").on_press(Message::Config(ConfigMessage::Gear(GearMessage::AddRings))),
-                ]
-                .spacing(SPACE),
-                // Gear Selection - Bracelets
+                    )
+                }),
+                // Gear filter, narrows the lists below
+                text("Gear Filter").size(SUBHEAD),
+                self.gear_filter_form(),
+                // Gear Selection - Pools
+                GearSlot::ALL.into_iter().fold(column![].spacing(SPACE), |pools, slot| {
+                    let pool = self.gear.pool(slot);
+                    pools.push(
+                        column![
+                            row![
+                                text(format!("{}:", slot.label())),
+                                self.field_error(&format!("items.{}", slot.key())),
+                            ]
+                            .spacing(SPACE),
+                            pool.selections
+                                .iter()
+                                .enumerate()
+                                .fold(column![].spacing(SPACE), |col, (idx, selection)| {
+                                    col.push(
+                                        row![
+                                            combo_box(
+                                                &pool.options,
+                                                &format!("Select {}...", slot.singular().to_lowercase()),
+                                                self.gear.option(selection.as_ref()),
+                                                move |option| Message::Config(ConfigMessage::Gear(GearMessage::Select(slot, idx, option.name))),
+                                            )
+                                            .on_option_hovered(|option| Message::Config(ConfigMessage::InspectItem(Some(option.name))))
+                                            .input_style(tier_input_style(self.tier_accent(selection.as_ref()))),
+                                            self.set_tag(selection.as_ref()),
+                                            button("?")
+                                                .on_press_maybe(selection.clone().map(|name| {
+                                                    Message::Config(ConfigMessage::InspectItem(Some(name)))
+                                                }))
+                                                .padding(BTN_PAD),
+                                            button("Compare")
+                                                .on_press_maybe(selection.clone().map(|name| {
+                                                    Message::ItemCompare(ItemCompareMessage::Add(name))
+                                                }))
+                                                .padding(BTN_PAD),
+                                            button("X")
+                                                .on_press(Message::Config(ConfigMessage::Gear(GearMessage::Remove(slot, idx))))
+                                                .padding(BTN_PAD),
+                                            self.pool_error(slot, idx),
+                                        ]
+                                        .spacing(SPACE)
+                                    )
+                                }),
+                            button(text(format!("Add {}", slot.singular())))
+                                .on_press(Message::Config(ConfigMessage::Gear(GearMessage::Add(slot)))),
+                        ]
+                        .spacing(SPACE)
+                    )
+                }),
+                // Gear Selection - Weapon
                 column![
-                    text("Bracelets:"),
-                    self.gear
-                        .bracelets_selections
-                        .iter()
-                        .enumerate()
-                        .fold(column![].spacing(SPACE), |col, (idx, selection)| {
-                            col.push(
-                                row![
-                                    combo_box(
-                                        &self.gear.bracelets,
-                                        "Select bracelet...",
-                                        selection.as_ref(),
-                                        move |name| Message::Config(ConfigMessage::Gear(GearMessage::BraceletsSelected(idx, name))),
-                                    ),
-                                    button("X")
-                                        .on_press(Message::Config(ConfigMessage::Gear(GearMessage::RemoveBracelets(idx))))
-                                        .padding(BTN_PAD),
-                                ]
-                                .spacing(SPACE)
-                            )
-                        }),
-                    button("Add Bracelet").on_press(Message::Config(ConfigMessage::Gear(GearMessage::AddBracelets))),
+                    text("Weapon:"),
+                    combo_box(
+                        &self.gear.weapons,
+                        "Select weapon...",
+                        self.gear.option(self.gear.selected_weapon.as_ref()),
+                        |option| Message::Config(ConfigMessage::Gear(GearMessage::WeaponSelected(option.name))),
+                    )
+                    .on_option_hovered(|option| Message::Config(ConfigMessage::InspectItem(Some(option.name))))
+                    .input_style(tier_input_style(self.tier_accent(self.gear.selected_weapon.as_ref()))),
+                    self.field_error("items.weapon"),
                 ]
                 .spacing(SPACE),
-                // Gear Selection - Necklaces
+                // Search space estimate
+                text("Search Space").size(SUBHEAD),
+                self.search_space(),
+                // Skill point check
+                text("Skill Points").size(SUBHEAD),
+                text("Pick one item per slot from the pools to see the fewest skill points needed to wear them with the weapon, and the order to put them on in.").size(TEXT),
+                self.skill_points_form(),
+                // Set bonuses
+                text("Sets").size(SUBHEAD),
+                text("Sets with pieces in the pools, with the stats of those pieces plus the set bonus for wearing them together. Full sets come first.").size(TEXT),
+                self.set_summaries(),
+                // Bulk import
+                text("Bulk Import").size(SUBHEAD),
+                text("Paste item names separated by commas or new lines, or a wynnbuilder build URL. Each item is added to the pool matching its type.").size(TEXT),
+                text_editor(&self.bulk_import)
+                    .placeholder("Item names or build URL...")
+                    .on_action(|action| Message::Config(ConfigMessage::BulkImport(BulkImportMessage::Edit(action))))
+                    .height(Length::Fixed(120.0)),
+                button("Import")
+                    .on_press(Message::Config(ConfigMessage::BulkImport(BulkImportMessage::Import)))
+                    .padding(BTN_PAD),
+                self.import_report(),
+                // Fill pools wizard
+                text("Fill Pools").size(SUBHEAD),
+                text("Pick the top items of each gear type by a stat. Applying replaces the current pools; the weapon is left as is.").size(TEXT),
+                self.fill_pools_form(),
+                self.fill_pools_preview(),
+                // Illegal combinations
+                text("Illegal Combinations").size(SUBHEAD),
+                text("Builds containing every item of a combination are skipped by the builder.").size(TEXT),
                 column![
-                    text("Necklaces:"),
-                    self.gear
-                        .necklaces_selections
+                    self.config
+                        .items
+                        .illegal_combinations
                         .iter()
+                        .flatten()
                         .enumerate()
-                        .fold(column![].spacing(SPACE), |col, (idx, selection)| {
+                        .fold(column![].spacing(SPACE), |col, (group_idx, group)| {
+                            let pooled: Vec<String> = self
+                                .config
+                                .items
+                                .pooled()
+                                .filter(|name| !group.contains(name))
+                                .cloned()
+                                .collect();
+
                             col.push(
-                                row![
-                                    combo_box(
-                                        &self.gear.necklaces,
-                                        "Select necklace...",
-                                        selection.as_ref(),
-                                        move |name| Message::Config(ConfigMessage::Gear(GearMessage::NecklacesSelected(idx, name))),
-                                    ),
-                                    button("X")
-                                        .on_press(Message::Config(ConfigMessage::Gear(GearMessage::RemoveNecklaces(idx))))
-                                        .padding(BTN_PAD),
+                                column![
+                                    group
+                                        .iter()
+                                        .enumerate()
+                                        .fold(row![].spacing(SPACE), |chips, (item_idx, name)| {
+                                            chips.push(
+                                                button(text(format!("{} X", name)).size(TEXT))
+                                                    .on_press(Message::Config(ConfigMessage::IllegalCombination(
+                                                        IllegalCombinationMessage::RemoveItem(group_idx, item_idx),
+                                                    )))
+                                                    .style(button::secondary)
+                                                    .padding(BTN_PAD),
+                                            )
+                                        })
+                                        .wrap(),
+                                    row![
+                                        pick_list(pooled, None::<String>, move |name| {
+                                            Message::Config(ConfigMessage::IllegalCombination(
+                                                IllegalCombinationMessage::AddItem(group_idx, name),
+                                            ))
+                                        })
+                                        .placeholder("Add item from pools...")
+                                        .padding(INPUT_PAD),
+                                        button("Remove Combination")
+                                            .on_press(Message::Config(ConfigMessage::IllegalCombination(
+                                                IllegalCombinationMessage::RemoveGroup(group_idx),
+                                            )))
+                                            .padding(BTN_PAD),
+                                    ]
+                                    .spacing(SPACE),
+                                    self.field_error(&format!("items.illegal_combinations.{}", group_idx)),
                                 ]
                                 .spacing(SPACE)
+                                .padding(SPACE),
                             )
                         }),
-                    button("Add Necklace").on_press(Message::Config(ConfigMessage::Gear(GearMessage::AddNecklaces))),
-                ]
-                .spacing(SPACE),
-                // Gear Selection - Weapon
-                column![
-                    text("Weapon:"),
-                    combo_box(
-                        &self.gear.weapons,
-                        "Select weapon...",
-                        self.gear.selected_weapon.as_ref(),
-                        |name| Message::Config(ConfigMessage::Gear(GearMessage::WeaponSelected(name))),
-                    ),
+                    pick_list(self.config.items.pooled().cloned().collect::<Vec<_>>(), None::<String>, |name| {
+                        Message::Config(ConfigMessage::IllegalCombination(IllegalCombinationMessage::AddGroup(name)))
+                    })
+                    .placeholder("Add combination starting with...")
+                    .padding(INPUT_PAD),
                 ]
                 .spacing(SPACE),
             ]
@@ -745,19 +477,903 @@ impl ConfigFile {
             .align_x(Horizontal::Left);
 
         // Wrap the content in a container with padding before scrollable
-        container(
-            scrollable(
-                container(content)
-                    .padding(SPACE) // Add padding around the content
-                    .width(Length::Fill),
-            )
-            .width(Length::Fill)
-            .height(Length::Fill),
+        let form = scrollable(
+            container(content)
+                .padding(SPACE) // Add padding around the content
+                .width(Length::Fill),
         )
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        let body = row![]
+            .push_maybe(self.raw_mode.then(|| self.raw_editor()))
+            .push(form)
+            .push_maybe(self.item_panel())
+            .spacing(SPACE);
+
+        container(body)
         .align_x(Horizontal::Center)
         .align_y(Vertical::Top)
         .width(Length::Fill)
         .height(Length::Fill)
         .into()
     }
-} 
\ No newline at end of file
+
+    fn skill_points_form(&self) -> Element<'_, Message> {
+        let pickers = EQUIP_SLOTS.iter().enumerate().fold(
+            column![].spacing(SPACE),
+            |col, (idx, (slot, label))| {
+                col.push(
+                    row![
+                        text(format!("{}:", label)).width(Length::Fixed(LABEL_WIDTH)),
+                        pick_list(slot.pool(&self.config.items).as_slice(), self.skill_point_items[idx].clone(), move |name| {
+                            Message::Config(ConfigMessage::SkillPoints(SkillPointMessage::Selected(idx, name)))
+                        })
+                        .placeholder("None")
+                        .padding(INPUT_PAD),
+                    ]
+                    .spacing(SPACE)
+                    .align_y(Vertical::Center),
+                )
+            },
+        );
+        let weapon = match self.config.items.weapon.as_str() {
+            "" => "none".to_string(),
+            weapon => weapon.to_string(),
+        };
+
+        column![
+            pickers,
+            row![
+                text(format!("Weapon: {}", weapon)).size(TEXT),
+                button("Clear")
+                    .on_press(Message::Config(ConfigMessage::SkillPoints(SkillPointMessage::Clear)))
+                    .style(button::secondary)
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            self.skill_points_result(),
+        ]
+        .spacing(SPACE)
+        .into()
+    }
+
+    fn skill_points_result(&self) -> Element<'_, Message> {
+        let allocation = match &self.skill_points {
+            None => return Space::with_height(0).into(),
+            Some(Err(error)) => return text(format!("Can't be worn together: {}", error)).size(TEXT).color(ERROR).into(),
+            Some(Ok(allocation)) => allocation,
+        };
+
+        let available = self.config.player.available_point as i32;
+        let spent = allocation.spent();
+        let summary = if spent <= available {
+            text(format!("Needs {} of {} available points.", spent, available)).size(TEXT)
+        } else {
+            text(format!("Needs {} points, {} more than the {} available.", spent, spent - available, available))
+                .size(TEXT)
+                .color(ERROR)
+        };
+        let per_skill = |points: &[i32; 5]| {
+            SKILLS
+                .iter()
+                .zip(points)
+                .map(|(skill, points)| format!("{} {}", skill, points))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        // The min point thresholds are checked against the totals with every bonus in
+        let minimums = self.config.threshold_fifth.as_ref().map_or([None; 5], |fifth| {
+            [
+                fifth.min_earth_point,
+                fifth.min_thunder_point,
+                fifth.min_water_point,
+                fifth.min_fire_point,
+                fifth.min_air_point,
+            ]
+        });
+        let short: Vec<String> = SKILLS
+            .iter()
+            .zip(minimums)
+            .zip(allocation.totals)
+            .filter_map(|((skill, minimum), total)| {
+                let minimum = minimum? as i32;
+                let missing = minimum - total;
+                (missing > 0).then(|| format!("{} {} short of {}", skill, missing, minimum))
+            })
+            .collect();
+
+        column![
+            summary,
+            text(format!("Assign: {}", per_skill(&allocation.assigned))).size(TEXT),
+            text(format!("Totals with item bonuses: {}", per_skill(&allocation.totals))).size(TEXT),
+            text(format!("Equip order: {}", allocation.order.join(", "))).size(TEXT),
+        ]
+        .push_maybe((!short.is_empty()).then(|| {
+            text(format!("Below the min point thresholds: {}", short.join(", ")))
+                .size(TEXT)
+                .color(WARNING)
+        }))
+        .spacing(SPACE)
+        .into()
+    }
+
+    /// Where the item lists came from, or that they're still loading.
+    fn items_status(&self) -> String {
+        if self.items_loading {
+            return "Loading items.json...".to_string();
+        }
+        let source = match (&self.items_cache_error, self.items_from_cache) {
+            (Some(e), _) => format!("read from items.json. {}", e),
+            (None, true) => "read from the item cache".to_string(),
+            (None, false) => "read from items.json and cached".to_string(),
+        };
+        format!(
+            "{} items for game version {}, {}.",
+            self.gear_list.items.len(),
+            self.game_version.as_deref().unwrap_or("unknown"),
+            source
+        )
+    }
+
+    /// Tier, level and class filters for the gear lists.
+    fn gear_filter_form(&self) -> Element<'_, Message> {
+        let filter = &self.gear_filter;
+        let filter_message = |message| Message::Config(ConfigMessage::GearFilter(message));
+        let shown: usize = self
+            .gear
+            .pools
+            .values()
+            .map(|pool| pool.options.options().len())
+            .sum::<usize>()
+            + self.gear.weapons.options().len();
+
+        column![
+            row![text("Tiers:").width(Length::Fixed(LABEL_WIDTH))]
+                .extend(filter.tier_options.iter().map(|tier| {
+                    let accent = tier_accent(tier);
+                    checkbox(tier, filter.tiers.contains(tier))
+                        .on_toggle(move |enabled| filter_message(GearFilterMessage::TierToggled(tier.clone(), enabled)))
+                        .size(TEXT)
+                        .style(move |theme, status| checkbox::Style {
+                            text_color: accent,
+                            ..checkbox::primary(theme, status)
+                        })
+                        .into()
+                }))
+                .spacing(SPACE)
+                .align_y(Vertical::Center)
+                .wrap(),
+            row![
+                text("Level Range:").width(Length::Fixed(LABEL_WIDTH)),
+                text_input("Any", &filter.min_level)
+                    .on_input(move |input| filter_message(GearFilterMessage::MinLevelChanged(input)))
+                    .size(TEXT)
+                    .padding(INPUT_PAD)
+                    .width(Length::Fixed(120.0)),
+                text("to"),
+                text_input("Any", &filter.max_level)
+                    .on_input(move |input| filter_message(GearFilterMessage::MaxLevelChanged(input)))
+                    .size(TEXT)
+                    .padding(INPUT_PAD)
+                    .width(Length::Fixed(120.0)),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            row![
+                text("Class:").width(Length::Fixed(LABEL_WIDTH)),
+                pick_list(&Class::ALL[..], filter.class, move |class| {
+                    filter_message(GearFilterMessage::ClassSelected(Some(class)))
+                })
+                .placeholder("Any class")
+                .padding(INPUT_PAD),
+                button("Any")
+                    .on_press_maybe(filter.class.map(|_| filter_message(GearFilterMessage::ClassSelected(None))))
+                    .style(button::secondary)
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            row![
+                text(format!("{} of {} items shown in the lists below.", shown, self.gear_list.items.len())).size(TEXT),
+                button("Reset Filter")
+                    .on_press_maybe(filter.is_active().then(|| filter_message(GearFilterMessage::Reset)))
+                    .style(button::secondary)
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+        ]
+        .spacing(SPACE)
+        .into()
+    }
+
+    /// Tier colour of a selected item, if it has one.
+    fn tier_accent(&self, name: Option<&String>) -> Option<Color> {
+        tier_accent(&self.gear_list.find(name?)?.tier)
+    }
+
+    /// Name of the set a selected item belongs to, in the set tier's colour.
+    fn set_tag(&self, name: Option<&String>) -> Element<'_, Message> {
+        match name.and_then(|name| self.gear_list.find(name)).and_then(|gear| self.sets.set_of(gear)) {
+            Some(set) => text(format!("{} set", set)).size(TEXT).color(tier_color("Set")).into(),
+            None => Space::with_width(0).into(),
+        }
+    }
+
+    fn set_summaries(&self) -> Element<'_, Message> {
+        if self.sets.sets.is_empty() {
+            return text("items.json has no set definitions.").size(TEXT).into();
+        }
+        if self.set_summaries.is_empty() {
+            return text("No set items in the pools.").size(TEXT).into();
+        }
+
+        self.set_summaries
+            .iter()
+            .fold(column![].spacing(SPACE), |col, summary| {
+                let totals: Vec<String> = summary
+                    .totals
+                    .iter()
+                    .map(|(label, total)| format!("{} {:+}", label, total))
+                    .collect();
+                // Incomplete sets are shown but can't give their full bonus
+                let color = if summary.is_full() { tier_color("Set") } else { WARNING };
+                col.push(
+                    column![
+                        text(format!("{} ({}/{} pieces)", summary.name, summary.pieces.len(), summary.size))
+                            .color(color),
+                        text(format!("Pieces: {}", summary.pieces.join(", "))).size(TEXT),
+                        text(format!("Totals: {}", list_or_none(&totals))).size(TEXT),
+                    ]
+                    .spacing(SPACE / 2),
+                )
+            })
+            .into()
+    }
+
+    /// Detail tooltip of the inspected item, if it's in items.json.
+    fn item_panel(&self) -> Option<Element<'_, Message>> {
+        let gear = self.gear_list.find(self.inspected_item.as_ref()?)?;
+
+        Some(
+            column![
+                button("Close")
+                    .on_press(Message::Config(ConfigMessage::InspectItem(None)))
+                    .style(button::secondary)
+                    .padding(BTN_PAD),
+                item_view::item_tooltip(gear),
+            ]
+            .spacing(SPACE)
+            .padding(SPACE)
+            .into(),
+        )
+    }
+
+    /// The whole config as TOML; valid edits are applied to the form as they're typed.
+    fn raw_editor(&self) -> Element<'_, Message> {
+        let status = match &self.raw_error {
+            Some(error) => text(error).size(TEXT).font(Font::MONOSPACE).color(ERROR),
+            None => text("Valid TOML, changes are saved as you type.").size(TEXT),
+        };
+
+        column![
+            text("config.toml").size(SUBHEAD),
+            text_editor(&self.raw_text)
+                .on_action(|action| Message::Config(ConfigMessage::RawEdit(action)))
+                .font(Font::MONOSPACE)
+                .height(Length::Fill),
+            status,
+        ]
+        .spacing(SPACE)
+        .padding(SPACE)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    /// Text for a numeric input: what was typed if it didn't parse, else the config value.
+    fn draft_or(&self, key: &str, value: impl ToString) -> String {
+        self.drafts.get(key).cloned().unwrap_or_else(|| value.to_string())
+    }
+
+    /// Inline validation message for the field at `key`, or nothing when it is valid.
+    fn field_error(&self, key: &str) -> Element<'_, Message> {
+        match (self.errors.get(key), self.errors.warning(key)) {
+            (Some(error), _) => text(error).size(TEXT).color(ERROR).into(),
+            (None, Some(warning)) => text(warning).size(TEXT).color(WARNING).into(),
+            (None, None) => Space::with_width(0).into(),
+        }
+    }
+
+    /// Section title with a toggle; disabling the section removes it from the config until
+    /// it is enabled again.
+    fn threshold_header(&self, section: ThresholdSection) -> Element<'_, Message> {
+        row![
+            text(section.title()).size(SUBHEAD),
+            checkbox("Enabled", self.is_threshold_enabled(section))
+                .on_toggle(move |enabled| Message::Config(ConfigMessage::ThresholdToggled(section, enabled)))
+                .size(TEXT)
+                .spacing(SPACE),
+        ]
+        .spacing(SPACE)
+        .align_y(Vertical::Center)
+        .into()
+    }
+
+    fn threshold_row(&self, field: &'static ThresholdField) -> Element<'_, Message> {
+        let value = self
+            .drafts
+            .get(&field.path())
+            .cloned()
+            .or_else(|| field.value(&self.config).map(|value| value.to_string()))
+            .unwrap_or_default();
+
+        row![
+            text(format!("{}:", field.label)).width(Length::Fixed(LABEL_WIDTH)),
+            text_input(
+                &format!(
+                    "Enter {} ({} to {})...",
+                    field.label.to_lowercase(),
+                    field.range.start(),
+                    field.range.end()
+                ),
+                &value
+            )
+            .on_input(|input| Message::Config(ConfigMessage::ThresholdChanged(field.section, field.key, input)))
+            .size(TEXT)
+            .padding(INPUT_PAD)
+            .width(Length::Fill),
+            text(field.unit).size(TEXT),
+            self.field_error(&field.path()),
+        ]
+        .spacing(SPACE)
+        .align_y(Vertical::Center)
+        .into()
+    }
+
+    /// Changes in the last items.json update, pooled items first since their configs need re-running.
+    fn item_changelog(&self) -> Element<'_, Message> {
+        let Some(changelog) = &self.item_changelog else {
+            return Space::with_height(0).into();
+        };
+        let affected = changelog.affected(&self.config.items);
+        let versions = format!(
+            "{} -> {}",
+            changelog.from_version.as_deref().unwrap_or("unknown version"),
+            changelog.to_version.as_deref().unwrap_or("unknown version")
+        );
+
+        let mut content = column![
+            row![
+                text(format!("Item Database Changes ({})", versions)).size(SUBHEAD),
+                button("Dismiss")
+                    .on_press(Message::Config(ConfigMessage::DismissChangelog))
+                    .style(button::secondary)
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            text(format!(
+                "{} added, {} removed, {} changed.",
+                changelog.added.len(),
+                changelog.removed.len(),
+                changelog.changed.len()
+            ))
+            .size(TEXT),
+        ]
+        .spacing(SPACE);
+
+        content = content.push(if affected.is_empty() {
+            text("None of the pooled items changed.").size(TEXT)
+        } else {
+            text(format!(
+                "Pooled items that changed: {}. Configs using them should be re-run.",
+                affected.join(", ")
+            ))
+            .size(TEXT)
+            .color(WARNING)
+        });
+        if !changelog.added.is_empty() {
+            content = content.push(text("Added:").size(TEXT)).push(changelog_names(changelog.added.iter(), &affected));
+        }
+        if !changelog.removed.is_empty() {
+            content = content.push(text("Removed:").size(TEXT)).push(changelog_names(changelog.removed.iter(), &affected));
+        }
+        if !changelog.changed.is_empty() {
+            content = content.push(text("Changed:").size(TEXT));
+        }
+        // Pooled items get a line with what changed, the rest are only named
+        let (pooled, other): (Vec<&ItemChange>, Vec<&ItemChange>) = changelog
+            .changed
+            .iter()
+            .partition(|change| affected.contains(&change.name.as_str()));
+        for change in pooled {
+            content = content.push(
+                text(format!("{}: {}", change.name, change.changes.join(", ")))
+                    .size(TEXT)
+                    .color(WARNING),
+            );
+        }
+        if !other.is_empty() {
+            content = content.push(changelog_names(other.into_iter().map(|change| &change.name), &affected));
+        }
+
+        container(content).padding(SPACE).style(container::bordered_box).into()
+    }
+
+    fn import_report(&self) -> Element<'_, Message> {
+        let Some(report) = &self.import_report else {
+            return Space::with_height(0).into();
+        };
+
+        column![
+            text(format!("Added {} item(s): {}", report.added.len(), report.added.join(", "))).size(TEXT),
+        ]
+        .push_maybe(report.replaced_weapon.as_ref().map(|previous| {
+            text(format!("Replaced the weapon {}", previous)).size(TEXT).color(WARNING)
+        }))
+        .push_maybe((!report.already_pooled.is_empty()).then(|| {
+            text(format!("Already in pools: {}", report.already_pooled.join(", ")))
+                .size(TEXT)
+                .color(WARNING)
+        }))
+        .push_maybe((!report.unknown.is_empty()).then(|| {
+            text(format!("Not recognized: {}", report.unknown.join(", ")))
+                .size(TEXT)
+                .color(ERROR)
+        }))
+        .spacing(SPACE)
+        .into()
+    }
+
+    /// Modal shown when config.toml exists but can't be loaded, until the user picks what to do.
+    pub fn load_error_dialog(&self) -> Option<Element<'_, Message>> {
+        let error = self.load_error.as_ref().filter(|_| !self.load_error_dismissed)?;
+        let load_error_message = |message| Message::Config(ConfigMessage::LoadError(message));
+
+        let dialog = column![
+            text(format!("{} could not be loaded", CONFIG_PATH)).size(SUBHEAD).color(ERROR),
+            text(error).size(TEXT),
+            text("Nothing will be saved until this is resolved, so the file is left as it is.").size(TEXT),
+            row![
+                button("Retry")
+                    .on_press(load_error_message(LoadErrorMessage::Retry))
+                    .padding(BTN_PAD),
+                button("Back Up and Start Fresh")
+                    .on_press(load_error_message(LoadErrorMessage::StartFresh))
+                    .padding(BTN_PAD),
+                button("Continue Without Saving")
+                    .on_press(load_error_message(LoadErrorMessage::Dismiss))
+                    .style(button::secondary)
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE),
+        ]
+        .spacing(SPACE)
+        .max_width(600);
+
+        Some(
+            container(dialog)
+                .padding(20)
+                .style(container::bordered_box)
+                .into(),
+        )
+    }
+
+    fn comparison_view<'a>(&'a self, comparison: &'a Comparison) -> Container<'a, Message> {
+        let header = row![
+            text("Field").width(Length::Fixed(250.0)),
+            text("Current (left)").width(Length::FillPortion(1)),
+            text(&comparison.path).width(Length::FillPortion(1)),
+            Space::with_width(Length::Fixed(200.0)),
+        ]
+        .spacing(SPACE);
+
+        let mut section = "";
+        let rows = comparison.diff.iter().fold(column![].spacing(SPACE), |rows, diff| {
+            let diff_section = diff.path.split('.').next().unwrap_or_default();
+            let rows = if diff_section != section {
+                section = diff_section;
+                rows.push(text(section).size(SUBHEAD))
+            } else {
+                rows
+            };
+            rows.push(comparison_row(diff))
+        });
+
+        let content = column![
+            text("Compare Configs").size(HEADER),
+            row![
+                text(format!("{} field(s) differ", comparison.diff.len())).size(SUBHEAD),
+                button("Close Comparison")
+                    .on_press(Message::Config(ConfigMessage::Compare(CompareMessage::Close)))
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            text("Take Left writes the current value into the other file after asking; Take Right copies the other file's value into the current config.").size(TEXT),
+            self.compare_status(),
+        ]
+        .push_maybe(comparison.pending_write.as_ref().map(|path| {
+            row![
+                text(format!("Overwrite {} in {}?", path, comparison.path)).size(TEXT).color(WARNING),
+                button("Write")
+                    .on_press(Message::Config(ConfigMessage::Compare(CompareMessage::ConfirmTakeLeft)))
+                    .style(button::danger)
+                    .padding(BTN_PAD),
+                button("Cancel")
+                    .on_press(Message::Config(ConfigMessage::Compare(CompareMessage::CancelTakeLeft)))
+                    .style(button::secondary)
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center)
+        }))
+        .push(header)
+        .push(rows)
+        .spacing(SPACE);
+
+        container(scrollable(container(content).padding(SPACE).width(Length::Fill)))
+            .width(Length::Fill)
+            .height(Length::Fill)
+    }
+
+    fn compare_status(&self) -> Element<'_, Message> {
+        match &self.compare_status {
+            Some(Ok(status)) => text(status).size(TEXT).into(),
+            Some(Err(error)) => text(error).size(TEXT).color(ERROR).into(),
+            None => Space::with_width(0).into(),
+        }
+    }
+
+    fn bundle_form(&self) -> Element<'_, Message> {
+        let bundle_message = |message| Message::Config(ConfigMessage::Bundle(message));
+
+        column![
+            row![
+                text("File:").width(Length::Fixed(LABEL_WIDTH)),
+                text_input("Enter file path...", &self.bundle_path)
+                    .on_input(move |input| bundle_message(BundleMessage::PathChanged(input)))
+                    .size(TEXT)
+                    .padding(INPUT_PAD),
+                button("Export").on_press(bundle_message(BundleMessage::ExportFile)).padding(BTN_PAD),
+                button("Import").on_press(bundle_message(BundleMessage::ImportFile)).padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            checkbox("Include the referenced items from items.json", self.bundle_include_items)
+                .on_toggle(move |include| bundle_message(BundleMessage::IncludeItemsToggled(include)))
+                .size(TEXT)
+                .spacing(SPACE),
+            text_editor(&self.bundle_text)
+                .placeholder("Config string or pasted config.toml...")
+                .on_action(move |action| bundle_message(BundleMessage::Edit(action)))
+                .height(Length::Fixed(80.0)),
+            row![
+                button("Export as String").on_press(bundle_message(BundleMessage::ExportString)).padding(BTN_PAD),
+                button("Import from String").on_press(bundle_message(BundleMessage::ImportString)).padding(BTN_PAD),
+            ]
+            .spacing(SPACE),
+        ]
+        .push_maybe(self.bundle_status.as_ref().map(|status| match status {
+            Ok(status) => text(status).size(TEXT),
+            Err(error) => text(error).size(TEXT).color(ERROR),
+        }))
+        .spacing(SPACE)
+        .into()
+    }
+
+    fn pending_import(&self) -> Element<'_, Message> {
+        let Some(pending) = &self.pending_import else {
+            return Space::with_width(0).into();
+        };
+
+        let summary = if pending.diff.is_empty() {
+            "The imported config is identical to the current one.".to_string()
+        } else {
+            format!("{} field(s) would change:", pending.diff.len())
+        };
+        let items = (!pending.bundle.items.is_empty()).then(|| {
+            text(format!("Includes {} item(s) from items.json.", pending.bundle.items.len())).size(TEXT)
+        });
+
+        column![text(summary).size(TEXT)]
+            .extend(pending.diff.iter().map(|diff| {
+                text(format!(
+                    "{}: {} -> {}",
+                    diff.path,
+                    diff.current_text(),
+                    diff.other_text(),
+                ))
+                .size(TEXT)
+                .into()
+            }))
+            .push_maybe(items)
+            .push(
+                row![
+                    button("Apply Import")
+                        .on_press(Message::Config(ConfigMessage::Bundle(BundleMessage::Apply)))
+                        .padding(BTN_PAD),
+                    button("Discard")
+                        .on_press(Message::Config(ConfigMessage::Bundle(BundleMessage::Discard)))
+                        .style(button::secondary)
+                        .padding(BTN_PAD),
+                ]
+                .spacing(SPACE),
+            )
+            .spacing(SPACE)
+            .into()
+    }
+
+    fn presets(&self) -> Element<'_, Message> {
+        let preset_message = |message| Message::Config(ConfigMessage::Preset(message));
+        let selected = self.selected_preset.as_ref();
+
+        column![
+            row![
+                pick_list(self.presets.as_slice(), selected, move |preset| {
+                    preset_message(PresetMessage::Selected(preset))
+                })
+                .placeholder("Select preset...")
+                .padding(INPUT_PAD),
+                button("Merge")
+                    .on_press_maybe(selected.map(|_| preset_message(PresetMessage::Apply(PresetMode::Merge))))
+                    .padding(BTN_PAD),
+                button("Replace")
+                    .on_press_maybe(selected.map(|_| preset_message(PresetMessage::Apply(PresetMode::Replace))))
+                    .padding(BTN_PAD),
+                button("Delete")
+                    .on_press_maybe(
+                        selected
+                            .filter(|preset| preset.path.is_some())
+                            .map(|_| preset_message(PresetMessage::Delete)),
+                    )
+                    .style(button::secondary)
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            row![
+                text_input("Preset name...", &self.preset_name)
+                    .on_input(move |input| preset_message(PresetMessage::NameChanged(input)))
+                    .size(TEXT)
+                    .padding(INPUT_PAD)
+                    .width(Length::Fixed(300.0)),
+                button("Save Current as Preset")
+                    .on_press(preset_message(PresetMessage::Save))
+                    .padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+        ]
+        .push_maybe(
+            selected
+                .filter(|preset| !preset.description.is_empty())
+                .map(|preset| text(&preset.description).size(TEXT)),
+        )
+        .push_maybe(self.preset_status.as_ref().map(|status| match status {
+            Ok(status) => text(status).size(TEXT),
+            Err(error) => text(error).size(TEXT).color(ERROR),
+        }))
+        .spacing(SPACE)
+        .into()
+    }
+
+    fn search_space(&self) -> Element<'_, Message> {
+        let combinations = self.estimate.combinations;
+        let rate = self.combos_rate();
+        let runtime = rate.and_then(|rate| estimate::runtime(combinations, rate));
+
+        let runtime_text = match runtime {
+            Some(runtime) => format!("Estimated runtime: {}", estimate::format_duration(runtime)),
+            None => "Enter combinations per second or finish a builder run to estimate the runtime".to_string(),
+        };
+        let rate_placeholder = match self.measured_rate {
+            Some(measured) => format!("Last run: {:.0}/s", measured),
+            None => "Enter combinations/s...".to_string(),
+        };
+        let too_large = combinations > LARGE_SEARCH || runtime.is_some_and(|runtime| runtime > LONG_RUNTIME);
+
+        column![
+            text(format!(
+                "Combinations: {}{}",
+                if self.estimate.approximate { "at least " } else { "" },
+                combinations
+            ))
+            .size(TEXT),
+            row![
+                text("Combinations/s:").width(Length::Fixed(LABEL_WIDTH)),
+                text_input(&rate_placeholder, &self.combos_per_second)
+                    .on_input(|input| Message::Config(ConfigMessage::CombosPerSecondChanged(input)))
+                    .size(TEXT)
+                    .padding(INPUT_PAD)
+                    .width(Length::Fixed(200.0)),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            text(runtime_text).size(TEXT),
+        ]
+        .push_maybe(too_large.then(|| {
+            text("This search space is very large; consider shrinking the pools or adding illegal combinations.")
+                .size(TEXT)
+                .color(WARNING)
+        }))
+        .spacing(SPACE)
+        .into()
+    }
+
+    fn fill_pools_form(&self) -> Element<'_, Message> {
+        let fill = &self.fill_pools;
+        let fill_message = |message| Message::Config(ConfigMessage::FillPools(message));
+
+        column![
+            row![
+                text("Level Range:").width(Length::Fixed(LABEL_WIDTH)),
+                text_input("Min level...", &fill.min_level)
+                    .on_input(move |input| fill_message(FillPoolsMessage::MinLevelChanged(input)))
+                    .size(TEXT)
+                    .padding(INPUT_PAD)
+                    .width(Length::Fixed(120.0)),
+                text("to"),
+                text_input("Max level...", &fill.max_level)
+                    .on_input(move |input| fill_message(FillPoolsMessage::MaxLevelChanged(input)))
+                    .size(TEXT)
+                    .padding(INPUT_PAD)
+                    .width(Length::Fixed(120.0)),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            row![text("Tiers:").width(Length::Fixed(LABEL_WIDTH))]
+                .extend(fill.tier_options.iter().map(|tier| {
+                    checkbox(tier, fill.tiers.contains(tier))
+                        .on_toggle(move |enabled| fill_message(FillPoolsMessage::TierToggled(tier.clone(), enabled)))
+                        .size(TEXT)
+                        .into()
+                }))
+                .spacing(SPACE)
+                .align_y(Vertical::Center)
+                .wrap(),
+            row![
+                text("Sort By:").width(Length::Fixed(LABEL_WIDTH)),
+                pick_list(fill.stat_options.as_slice(), fill.sort_stat.clone(), move |stat| {
+                    fill_message(FillPoolsMessage::SortStatSelected(stat))
+                })
+                .placeholder("Select stat...")
+                .padding(INPUT_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            row![
+                text("Items per Pool:").width(Length::Fixed(LABEL_WIDTH)),
+                text_input("Enter count...", &fill.count)
+                    .on_input(move |input| fill_message(FillPoolsMessage::CountChanged(input)))
+                    .size(TEXT)
+                    .padding(INPUT_PAD)
+                    .width(Length::Fixed(120.0)),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+            button("Preview")
+                .on_press(fill_message(FillPoolsMessage::Preview))
+                .padding(BTN_PAD),
+        ]
+        .spacing(SPACE)
+        .into()
+    }
+
+    fn fill_pools_preview(&self) -> Element<'_, Message> {
+        let preview = match &self.fill_pools.preview {
+            None => return Space::with_width(0).into(),
+            Some(Err(error)) => return text(error).size(TEXT).color(ERROR).into(),
+            Some(Ok(preview)) => preview,
+        };
+
+        preview
+            .iter()
+            .fold(column![].spacing(SPACE), |col, (slot, names)| {
+                if names.is_empty() {
+                    return col.push(text(format!("{}: no matching items", slot.label())).size(TEXT).color(WARNING));
+                }
+                let header = text(format!("{} ({}):", slot.label(), names.len())).size(TEXT);
+                col.push(
+                    row![header]
+                        .extend(names.iter().map(|name| {
+                            text(name)
+                                .size(TEXT)
+                                .color_maybe(self.tier_accent(Some(name)))
+                                .into()
+                        }))
+                        .spacing(SPACE)
+                        .wrap(),
+                )
+            })
+            .push(
+                row![
+                    button("Apply")
+                        .on_press(Message::Config(ConfigMessage::FillPools(FillPoolsMessage::Apply)))
+                        .padding(BTN_PAD),
+                    button("Discard")
+                        .on_press(Message::Config(ConfigMessage::FillPools(FillPoolsMessage::Discard)))
+                        .style(button::secondary)
+                        .padding(BTN_PAD),
+                ]
+                .spacing(SPACE),
+            )
+            .into()
+    }
+
+    fn pool_error(&self, slot: GearSlot, idx: usize) -> Element<'_, Message> {
+        match self.gear.pool(slot).pool_index(idx) {
+            Some(pool_idx) => self.field_error(&format!("items.{}.{}", slot.key(), pool_idx)),
+            None => Space::with_width(0).into(),
+        }
+    }
+}
+
+/// One differing field; gear pools show which items each side has that the other lacks.
+fn comparison_row(diff: &FieldDiff) -> Element<'_, Message> {
+    let (current, other) = match diff.set_difference() {
+        Some((only_current, only_other)) => (
+            format!("only here: {}", list_or_none(&only_current)),
+            format!("only there: {}", list_or_none(&only_other)),
+        ),
+        None => (diff.current_text(), diff.other_text()),
+    };
+
+    row![
+        text(&diff.path).size(TEXT).width(Length::Fixed(250.0)),
+        text(current).size(TEXT).width(Length::FillPortion(1)),
+        text(other).size(TEXT).width(Length::FillPortion(1)),
+        row![
+            button("Take Left")
+                .on_press(Message::Config(ConfigMessage::Compare(CompareMessage::TakeLeft(diff.path.clone()))))
+                .padding(BTN_PAD),
+            button("Take Right")
+                .on_press(Message::Config(ConfigMessage::Compare(CompareMessage::TakeRight(diff.path.clone()))))
+                .padding(BTN_PAD),
+        ]
+        .spacing(SPACE)
+        .width(Length::Fixed(200.0)),
+    ]
+    .spacing(SPACE)
+    .align_y(Vertical::Center)
+    .into()
+}
+
+fn list_or_none(names: &[String]) -> String {
+    if names.is_empty() {
+        "nothing".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Colours the text of a gear combo box by the selected item's tier.
+fn tier_input_style(accent: Option<Color>) -> impl Fn(&Theme, text_input::Status) -> text_input::Style {
+    move |theme, status| {
+        let style = text_input::default(theme, status);
+        text_input::Style {
+            value: accent.unwrap_or(style.value),
+            ..style
+        }
+    }
+}
+
+/// Item names of one changelog section. Pooled items come first and are highlighted,
+/// the rest is capped so a large patch doesn't swamp the page.
+fn changelog_names<'a>(names: impl Iterator<Item = &'a String>, affected: &[&str]) -> Element<'a, Message> {
+    let (pooled, other): (Vec<&String>, Vec<&String>) = names.partition(|name| affected.contains(&name.as_str()));
+    let hidden = other.len().saturating_sub(CHANGELOG_LIMIT);
+
+    row(pooled
+        .into_iter()
+        .map(|name| text(name).size(TEXT).color(WARNING).into())
+        .chain(other.into_iter().take(CHANGELOG_LIMIT).map(|name| text(name).size(TEXT).into())))
+    .push_maybe((hidden > 0).then(|| text(format!("and {} more", hidden)).size(TEXT)))
+    .spacing(SPACE)
+    .wrap()
+    .into()
+}
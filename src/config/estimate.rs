@@ -0,0 +1,210 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::build_config::Items;
+
+use super::GearSlot;
+
+/// Above this many combinations a run is flagged as unreasonably large.
+pub const LARGE_SEARCH: u128 = 10_000_000_000;
+/// Above this estimated runtime a run is flagged as unreasonably long.
+pub const LONG_RUNTIME: Duration = Duration::from_secs(24 * 60 * 60);
+/// Exact inclusion–exclusion visits every subset of groups, so it's capped.
+const MAX_EXACT_GROUPS: usize = 16;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Estimate {
+    pub combinations: u128,
+    /// Too many illegal combinations to count exactly; `combinations` is a lower bound.
+    pub approximate: bool,
+}
+
+/// Number of builds the builder will try for the current pools.
+///
+/// The two ring slots take an unordered pair from the ring pool and may hold the
+/// same ring twice. A name listed twice in a pool is one item. Builds containing every item of an illegal combination are
+/// subtracted using inclusion–exclusion over the combinations. An empty group would
+/// match every build, so those are skipped.
+pub fn combinations(items: &Items) -> Estimate {
+    let groups: Vec<&Vec<String>> = items
+        .illegal_combinations
+        .iter()
+        .flatten()
+        .filter(|group| !group.is_empty())
+        .collect();
+    let total = matching(items, &[]);
+
+    if groups.len() > MAX_EXACT_GROUPS {
+        // Subtracting each group alone over-counts builds hit by several groups
+        let excluded = groups
+            .iter()
+            .fold(0u128, |sum, group| sum.saturating_add(matching(items, &group_items(group))));
+        return Estimate {
+            combinations: total.saturating_sub(excluded),
+            approximate: true,
+        };
+    }
+
+    let mut excluded: i128 = 0;
+    for mask in 1u32..(1 << groups.len()) {
+        let required: Vec<&str> = groups
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| mask & (1 << idx) != 0)
+            .flat_map(|(_, group)| group_items(group))
+            .collect();
+        let count = matching(items, &required) as i128;
+        if mask.count_ones() % 2 == 1 {
+            excluded += count;
+        } else {
+            excluded -= count;
+        }
+    }
+
+    Estimate {
+        combinations: total.saturating_sub(excluded.max(0) as u128),
+        approximate: false,
+    }
+}
+
+fn group_items(group: &[String]) -> Vec<&str> {
+    group.iter().map(String::as_str).collect()
+}
+
+/// Builds that contain every item in `required`.
+fn matching(items: &Items, required: &[&str]) -> u128 {
+    let mut required: Vec<&str> = required.to_vec();
+    required.sort_unstable();
+    required.dedup();
+
+    let mut count: u128 = 1;
+    let mut placed = 0;
+    for slot in GearSlot::ALL {
+        let pool: BTreeSet<&str> = slot.pool(items).iter().map(String::as_str).collect();
+        let n = pool.len() as u128;
+        let fixed = required.iter().filter(|name| pool.contains(*name)).count();
+        placed += fixed;
+
+        let choices = match (slot, fixed) {
+            (GearSlot::Ring, 0) => n * (n + 1) / 2,
+            (GearSlot::Ring, 1) => n,
+            (GearSlot::Ring, 2) => 1,
+            (_, 0) => n,
+            (_, 1) => 1,
+            _ => 0,
+        };
+        count = count.saturating_mul(choices);
+    }
+
+    // The weapon is fixed, so a required item is either it or not in the build at all
+    if required.contains(&items.weapon.as_str()) {
+        placed += 1;
+    }
+    if placed < required.len() {
+        return 0;
+    }
+    count
+}
+
+/// Time to try `combinations` builds; more than a `Duration` can hold is clamped to `Duration::MAX`.
+pub fn runtime(combinations: u128, per_second: f64) -> Option<Duration> {
+    (per_second > 0.0).then(|| {
+        Duration::try_from_secs_f64(combinations as f64 / per_second).unwrap_or(Duration::MAX)
+    })
+}
+
+/// Largest two units of a duration, e.g. `3d 4h` or `12m 5s`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let units = [
+        (secs / 31_536_000, "y"),
+        (secs / 86_400 % 365, "d"),
+        (secs / 3_600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ];
+
+    let parts: Vec<String> = units
+        .iter()
+        .skip_while(|(value, _)| *value == 0)
+        .take(2)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+    if parts.is_empty() {
+        "under 1s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::names;
+
+    fn items() -> Items {
+        Items {
+            helmets: names(&["H1", "H2"]),
+            chest_plates: names(&["C1"]),
+            leggings: names(&["L1", "L2", "L3"]),
+            boots: names(&["B1"]),
+            rings: names(&["R1", "R2", "R3"]),
+            bracelets: names(&["Br1"]),
+            necklaces: names(&["N1", "N2"]),
+            weapon: "W".to_string(),
+            illegal_combinations: None,
+        }
+    }
+
+    #[test]
+    fn ring_pairs_are_unordered_and_may_repeat() {
+        // 2 * 1 * 3 * 1 * C(4, 2) * 1 * 2
+        assert_eq!(combinations(&items()).combinations, 72);
+    }
+
+    #[test]
+    fn repeated_pool_entries_count_once() {
+        let mut items = items();
+        items.helmets = names(&["H1", "H2", "H1"]);
+        items.rings = names(&["R1", "R2", "R3", "R2"]);
+        items.illegal_combinations = Some(vec![names(&["H1", "R2"])]);
+
+        // H1 + R2: 1 * 1 * 3 * 1 * 3 * 1 * 2 = 18 builds
+        assert_eq!(combinations(&items).combinations, 72 - 18);
+    }
+
+    #[test]
+    fn illegal_combinations_are_excluded_once() {
+        let mut items = items();
+        // H1 + L1: 1 * 1 * 1 * 1 * 6 * 1 * 2 = 12 builds
+        // H1 + R1: 1 * 1 * 3 * 1 * 3 * 1 * 2 = 18 builds, 6 of them shared with the first group
+        items.illegal_combinations = Some(vec![names(&["H1", "L1"]), names(&["H1", "R1"])]);
+
+        let estimate = combinations(&items);
+
+        assert_eq!(estimate.combinations, 72 - 12 - 18 + 6);
+        assert!(!estimate.approximate);
+    }
+
+    #[test]
+    fn groups_with_unpooled_items_exclude_nothing() {
+        let mut items = items();
+        items.illegal_combinations = Some(vec![names(&["H1", "Missing"]), names(&["H1", "H2"]), Vec::new()]);
+
+        assert_eq!(combinations(&items).combinations, 72);
+    }
+
+    #[test]
+    fn absurd_rates_clamp_the_runtime() {
+        assert_eq!(runtime(u128::MAX, 0.000001), Some(Duration::MAX));
+        assert_eq!(runtime(1_000, 0.0), None);
+        assert!(runtime(1_000, 0.000001).unwrap() > LONG_RUNTIME);
+    }
+
+    #[test]
+    fn durations_show_two_units() {
+        assert_eq!(format_duration(Duration::from_secs(90_061)), "1d 1h");
+        assert_eq!(format_duration(Duration::from_secs(65)), "1m 5s");
+        assert_eq!(format_duration(Duration::ZERO), "under 1s");
+    }
+}
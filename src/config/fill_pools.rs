@@ -0,0 +1,216 @@
+use std::collections::BTreeSet;
+
+use super::validation::{MAX_LEVEL, MIN_LEVEL};
+use super::{GearList, GearSlot};
+
+const DEFAULT_COUNT: usize = 15;
+
+/// Items picked for each pool, in the order they'll be written.
+pub type PoolPreview = Vec<(GearSlot, Vec<String>)>;
+
+/// State of the "Fill Pools" wizard. Inputs are kept as typed so partial
+/// numbers can be edited; they're only parsed when a preview is requested.
+#[derive(Debug, Clone)]
+pub struct FillPools {
+    pub min_level: String,
+    pub max_level: String,
+    /// Tiers present in items.json, in the order they were first seen.
+    pub tier_options: Vec<String>,
+    pub tiers: BTreeSet<String>,
+    /// Numeric item keys available for sorting.
+    pub stat_options: Vec<String>,
+    pub sort_stat: Option<String>,
+    pub count: String,
+    pub preview: Option<Result<PoolPreview, String>>,
+}
+
+impl Default for FillPools {
+    fn default() -> Self {
+        Self {
+            min_level: MIN_LEVEL.to_string(),
+            max_level: MAX_LEVEL.to_string(),
+            tier_options: Vec::new(),
+            tiers: BTreeSet::new(),
+            stat_options: Vec::new(),
+            sort_stat: None,
+            count: DEFAULT_COUNT.to_string(),
+            preview: None,
+        }
+    }
+}
+
+impl FillPools {
+    pub fn new(gear_list: &GearList) -> Self {
+        let mut tier_options: Vec<String> = Vec::new();
+        for gear in &gear_list.items {
+            if !tier_options.contains(&gear.tier) {
+                tier_options.push(gear.tier.clone());
+            }
+        }
+
+        Self {
+            tiers: tier_options.iter().cloned().collect(),
+            tier_options,
+            stat_options: gear_list.stat_keys(),
+            ..Default::default()
+        }
+    }
+
+    /// Picks the top items per pool, or explains which input is invalid.
+    pub fn select(&self, gear_list: &GearList) -> Result<PoolPreview, String> {
+        let min_level: i32 = self
+            .min_level
+            .parse()
+            .map_err(|_| "Min level must be a number".to_string())?;
+        let max_level: i32 = self
+            .max_level
+            .parse()
+            .map_err(|_| "Max level must be a number".to_string())?;
+        if min_level > max_level {
+            return Err("Min level is higher than max level".to_string());
+        }
+        let count: usize = match self.count.parse() {
+            Ok(count) if count > 0 => count,
+            _ => return Err("Items per pool must be a positive number".to_string()),
+        };
+        let stat = self
+            .sort_stat
+            .as_deref()
+            .ok_or_else(|| "Select a stat to sort by".to_string())?;
+
+        Ok(GearSlot::ALL
+            .into_iter()
+            .map(|slot| {
+                let mut candidates: Vec<_> = gear_list
+                    .items
+                    .iter()
+                    .filter(|gear| gear.gear_type == slot.gear_type())
+                    .filter(|gear| (min_level..=max_level).contains(&gear.lvl))
+                    .filter(|gear| self.tiers.contains(&gear.tier))
+                    .collect();
+
+                // Highest stat first; ties go to the higher level, then by name so previews are stable
+                candidates.sort_by(|a, b| {
+                    b.stat(stat)
+                        .total_cmp(&a.stat(stat))
+                        .then(b.lvl.cmp(&a.lvl))
+                        .then(a.name.cmp(&b.name))
+                });
+
+                let names = candidates
+                    .into_iter()
+                    .take(count)
+                    .map(|gear| gear.name.clone())
+                    .collect();
+                (slot, names)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list, names};
+    use crate::config::{Gear, GearType};
+
+    fn helmet(name: &str, lvl: i32, tier: &str, hp: i32) -> Gear {
+        Gear {
+            lvl,
+            tier: tier.to_string(),
+            hp,
+            ..gear(GearType::Helmet, name)
+        }
+    }
+
+    fn wizard(gear_list: &GearList) -> FillPools {
+        FillPools {
+            sort_stat: Some("hp".to_string()),
+            count: "2".to_string(),
+            ..FillPools::new(gear_list)
+        }
+    }
+
+    #[test]
+    fn top_items_are_picked_per_slot() {
+        let gear_list = gear_list([
+            helmet("Low", 100, "Rare", 100),
+            helmet("High", 100, "Rare", 900),
+            helmet("Mid", 100, "Rare", 500),
+            Gear { lvl: 100, hp: 50, ..gear(GearType::Ring, "Band") },
+        ]);
+
+        let preview = wizard(&gear_list).select(&gear_list).unwrap();
+
+        assert_eq!(preview[0], (GearSlot::Helmet, names(&["High", "Mid"])));
+        assert_eq!(preview[4], (GearSlot::Ring, names(&["Band"])));
+        assert!(preview[1].1.is_empty());
+    }
+
+    #[test]
+    fn ties_go_to_level_then_name() {
+        let gear_list = gear_list([
+            helmet("B", 90, "Rare", 500),
+            helmet("A", 90, "Rare", 500),
+            helmet("C", 100, "Rare", 500),
+        ]);
+        let mut fill = wizard(&gear_list);
+        fill.count = "3".to_string();
+
+        assert_eq!(fill.select(&gear_list).unwrap()[0].1, names(&["C", "A", "B"]));
+
+        // A stat no item has ties everything
+        fill.sort_stat = Some("noSuchStat".to_string());
+        assert_eq!(fill.select(&gear_list).unwrap()[0].1, names(&["C", "A", "B"]));
+    }
+
+    #[test]
+    fn level_range_and_tiers_filter_items() {
+        let gear_list = gear_list([
+            helmet("Too Low", 20, "Rare", 900),
+            helmet("Wrong Tier", 100, "Normal", 800),
+            helmet("Kept", 100, "Rare", 100),
+        ]);
+        let mut fill = wizard(&gear_list);
+        fill.min_level = "80".to_string();
+        fill.tiers.remove("Normal");
+        assert_eq!(fill.select(&gear_list).unwrap()[0].1, names(&["Kept"]));
+
+        fill.tiers.clear();
+        assert!(fill.select(&gear_list).unwrap()[0].1.is_empty());
+    }
+
+    #[test]
+    fn empty_items_json_gives_empty_pools() {
+        let gear_list = GearList::default();
+        let fill = wizard(&gear_list);
+
+        assert!(fill.tier_options.is_empty());
+        let preview = fill.select(&gear_list).unwrap();
+        assert_eq!(preview.len(), GearSlot::ALL.len());
+        assert!(preview.iter().all(|(_, names)| names.is_empty()));
+    }
+
+    #[test]
+    fn invalid_criteria_are_reported() {
+        let gear_list = GearList::default();
+        let cases: [(&str, &str, &str, Option<&str>); 6] = [
+            ("90", "80", "2", Some("hp")),
+            ("", "80", "2", Some("hp")),
+            ("1", "99999999999", "2", Some("hp")),
+            ("1", "106", "0", Some("hp")),
+            ("1", "106", "-1", Some("hp")),
+            ("1", "106", "2", None),
+        ];
+        for (min_level, max_level, count, stat) in cases {
+            let fill = FillPools {
+                min_level: min_level.to_string(),
+                max_level: max_level.to_string(),
+                count: count.to_string(),
+                sort_stat: stat.map(str::to_string),
+                ..FillPools::new(&gear_list)
+            };
+            assert!(fill.select(&gear_list).is_err(), "{} {} {} {:?}", min_level, max_level, count, stat);
+        }
+    }
+}
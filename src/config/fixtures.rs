@@ -0,0 +1,28 @@
+//! Items for tests. Start from `gear` and set what the test is about with struct
+//! update syntax, e.g. `Gear { hp: 3000, ..gear(GearType::Helmet, "Cumulonimbus") }`.
+
+use super::{Gear, GearList, GearType};
+
+/// A level 0 common item with no stats.
+pub(crate) fn gear(gear_type: GearType, name: &str) -> Gear {
+    Gear::default_for_type(gear_type, name, 0)
+}
+
+/// `gear` with identifications or other items.json keys set.
+pub(crate) fn with_stats(gear: Gear, stats: &[(&str, i64)]) -> Gear {
+    let mut gear = gear;
+    for (key, value) in stats {
+        gear.stats.insert(key.to_string(), (*value).into());
+    }
+    gear
+}
+
+pub(crate) fn gear_list(items: impl IntoIterator<Item = Gear>) -> GearList {
+    GearList {
+        items: items.into_iter().collect(),
+    }
+}
+
+pub(crate) fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
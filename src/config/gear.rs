@@ -1,100 +1,623 @@
-use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
 use iced_widget::combo_box;
+use serde::{Deserialize, Serialize};
+
+use crate::build_config::Items;
+
+use super::gear_filter::GearFilter;
+
+/// A multi-item gear pool in `[items]`. Each variant maps to one list in
+/// `build_config::Items`; the weapon is a single item and handled separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GearSlot {
+    Helmet,
+    Chestplate,
+    Leggings,
+    Boots,
+    Ring,
+    Bracelet,
+    Necklace,
+}
+
+impl GearSlot {
+    pub const ALL: [GearSlot; 7] = [
+        GearSlot::Helmet,
+        GearSlot::Chestplate,
+        GearSlot::Leggings,
+        GearSlot::Boots,
+        GearSlot::Ring,
+        GearSlot::Bracelet,
+        GearSlot::Necklace,
+    ];
+
+    /// Key of the pool in the `[items]` table.
+    pub fn key(self) -> &'static str {
+        match self {
+            GearSlot::Helmet => "helmets",
+            GearSlot::Chestplate => "chest_plates",
+            GearSlot::Leggings => "leggings",
+            GearSlot::Boots => "boots",
+            GearSlot::Ring => "rings",
+            GearSlot::Bracelet => "bracelets",
+            GearSlot::Necklace => "necklaces",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GearSlot::Helmet => "Helmets",
+            GearSlot::Chestplate => "Chestplates",
+            GearSlot::Leggings => "Leggings",
+            GearSlot::Boots => "Boots",
+            GearSlot::Ring => "Rings",
+            GearSlot::Bracelet => "Bracelets",
+            GearSlot::Necklace => "Necklaces",
+        }
+    }
+
+    pub fn singular(self) -> &'static str {
+        match self {
+            GearSlot::Helmet => "Helmet",
+            GearSlot::Chestplate => "Chestplate",
+            GearSlot::Leggings => "Leggings",
+            GearSlot::Boots => "Boots",
+            GearSlot::Ring => "Ring",
+            GearSlot::Bracelet => "Bracelet",
+            GearSlot::Necklace => "Necklace",
+        }
+    }
+
+    pub fn gear_type(self) -> GearType {
+        match self {
+            GearSlot::Helmet => GearType::Helmet,
+            GearSlot::Chestplate => GearType::Chestplate,
+            GearSlot::Leggings => GearType::Leggings,
+            GearSlot::Boots => GearType::Boots,
+            GearSlot::Ring => GearType::Ring,
+            GearSlot::Bracelet => GearType::Bracelet,
+            GearSlot::Necklace => GearType::Necklace,
+        }
+    }
+
+    pub fn from_gear_type(gear_type: &GearType) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|slot| slot.gear_type() == *gear_type)
+    }
+
+    pub fn pool(self, items: &Items) -> &Vec<String> {
+        match self {
+            GearSlot::Helmet => &items.helmets,
+            GearSlot::Chestplate => &items.chest_plates,
+            GearSlot::Leggings => &items.leggings,
+            GearSlot::Boots => &items.boots,
+            GearSlot::Ring => &items.rings,
+            GearSlot::Bracelet => &items.bracelets,
+            GearSlot::Necklace => &items.necklaces,
+        }
+    }
+
+    pub fn pool_mut(self, items: &mut Items) -> &mut Vec<String> {
+        match self {
+            GearSlot::Helmet => &mut items.helmets,
+            GearSlot::Chestplate => &mut items.chest_plates,
+            GearSlot::Leggings => &mut items.leggings,
+            GearSlot::Boots => &mut items.boots,
+            GearSlot::Ring => &mut items.rings,
+            GearSlot::Bracelet => &mut items.bracelets,
+            GearSlot::Necklace => &mut items.necklaces,
+        }
+    }
+}
+
+/// An item in a gear list. iced's combo box menu draws every option in one colour,
+/// so the tier is written after the name instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GearOption {
+    pub name: String,
+    pub tier: String,
+}
+
+impl GearOption {
+    pub fn new(gear: &Gear) -> Self {
+        Self {
+            name: gear.name.clone(),
+            tier: gear.tier.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for GearOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.tier)
+    }
+}
+
+/// Combo box options and the per-row selections of one gear pool.
+#[derive(Default)]
+pub struct GearPool {
+    pub options: combo_box::State<GearOption>,
+    pub selections: Vec<Option<String>>,
+}
+
+impl GearPool {
+    /// Position in the config pool of the item shown in row `idx`, if the row is filled.
+    pub fn pool_index(&self, idx: usize) -> Option<usize> {
+        self.selections
+            .get(idx)?
+            .as_ref()
+            .map(|_| self.selections[..idx].iter().flatten().count())
+    }
+}
 
 #[derive(Default)]
 pub struct GearSelections {
-    pub helmets: combo_box::State<String>,
-    pub helmet_selections: Vec<Option<String>>,
-    pub chestplates: combo_box::State<String>,
-    pub chestplate_selections: Vec<Option<String>>,
-    pub leggings: combo_box::State<String>,
-    pub leggings_selections: Vec<Option<String>>,
-    pub boots: combo_box::State<String>,
-    pub boots_selections: Vec<Option<String>>,
-    pub rings: combo_box::State<String>,
-    pub rings_selections: Vec<Option<String>>,
-    pub bracelets: combo_box::State<String>,
-    pub bracelets_selections: Vec<Option<String>>,
-    pub necklaces: combo_box::State<String>,
-    pub necklaces_selections: Vec<Option<String>>,
-    pub weapons: combo_box::State<String>,
+    pub pools: BTreeMap<GearSlot, GearPool>,
+    pub weapons: combo_box::State<GearOption>,
     pub selected_weapon: Option<String>,
+    /// Every item by name, so selections the filter hides still show their tier.
+    known: BTreeMap<String, GearOption>,
+}
+
+impl GearSelections {
+    pub fn new(
+        gear_list: &GearList,
+        items: &Items,
+        filter: &GearFilter,
+        class: Option<Class>,
+    ) -> Self {
+        let pools = GearSlot::ALL
+            .into_iter()
+            .map(|slot| {
+                let pool = GearPool {
+                    options: combo_box::State::default(),
+                    selections: slot.pool(items).iter().cloned().map(Some).collect(),
+                };
+                (slot, pool)
+            })
+            .collect();
+
+        let mut selections = Self {
+            pools,
+            weapons: combo_box::State::default(),
+            selected_weapon: (!items.weapon.is_empty()).then(|| items.weapon.clone()),
+            known: BTreeMap::new(),
+        };
+        selections.apply_filter(gear_list, filter, class);
+        selections
+    }
+
+    /// Rebuilds the option lists from the items `filter` lets through that
+    /// the player's class can use.
+    pub fn apply_filter(
+        &mut self,
+        gear_list: &GearList,
+        filter: &GearFilter,
+        class: Option<Class>,
+    ) {
+        let options = |gear_types: &[GearType]| {
+            let items = gear_types
+                .iter()
+                .flat_map(|gear_type| {
+                    gear_list
+                        .items
+                        .iter()
+                        .filter(move |gear| gear.gear_type == *gear_type)
+                })
+                .filter(|gear| filter.matches(gear))
+                .filter(|gear| class.is_none_or(|class| class.can_use(gear)))
+                .map(GearOption::new)
+                .collect();
+            combo_box::State::new(items)
+        };
+
+        self.known = gear_list
+            .items
+            .iter()
+            .map(|gear| (gear.name.clone(), GearOption::new(gear)))
+            .collect();
+        for (slot, pool) in &mut self.pools {
+            pool.options = options(&[slot.gear_type()]);
+        }
+        self.weapons = match class {
+            Some(class) => options(&[class.weapon_type()]),
+            None => options(&GearType::WEAPONS),
+        };
+    }
+
+    /// The option shown for a selected item, `None` when it isn't in items.json.
+    pub fn option(&self, name: Option<&String>) -> Option<&GearOption> {
+        self.known.get(name?)
+    }
+
+    pub fn pool(&self, slot: GearSlot) -> &GearPool {
+        &self.pools[&slot]
+    }
+
+    pub fn add(&mut self, slot: GearSlot) {
+        self.pool_mut(slot).selections.push(None);
+    }
+
+    /// Adds `name` as a new row unless the pool already holds it.
+    pub fn insert(&mut self, slot: GearSlot, name: String) -> bool {
+        let selections = &mut self.pool_mut(slot).selections;
+        if selections.contains(&Some(name.clone())) {
+            return false;
+        }
+        selections.push(Some(name));
+        true
+    }
+
+    /// Replaces every row of the pool with `names`.
+    pub fn set_pool(&mut self, slot: GearSlot, names: Vec<String>) {
+        self.pool_mut(slot).selections = names.into_iter().map(Some).collect();
+    }
+
+    /// Replaces the item in row `idx`, ignoring rows past the end.
+    pub fn select(&mut self, slot: GearSlot, idx: usize, name: String) {
+        if let Some(selection) = self.pool_mut(slot).selections.get_mut(idx) {
+            *selection = Some(name);
+        }
+    }
+
+    /// Removes row `idx` only, leaving other rows holding the same item untouched.
+    pub fn remove(&mut self, slot: GearSlot, idx: usize) {
+        let selections = &mut self.pool_mut(slot).selections;
+        if idx < selections.len() {
+            selections.remove(idx);
+        }
+    }
+
+    /// The selections are the source of truth for the pools: empty rows only
+    /// exist in the UI, every filled row becomes a pool entry in row order.
+    pub fn apply_to(&self, items: &mut Items) {
+        for (slot, pool) in &self.pools {
+            *slot.pool_mut(items) = pool.selections.iter().flatten().cloned().collect();
+        }
+        items.weapon = self.selected_weapon.clone().unwrap_or_default();
+    }
+
+    fn pool_mut(&mut self, slot: GearSlot) -> &mut GearPool {
+        self.pools.entry(slot).or_default()
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GearList {
     pub items: Vec<Gear>,
 }
 
 impl GearList {
-    pub fn from_json(path: &str) -> Result<Self, String> {
-        let items_json_string = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read items file: {}", e))?;
-
-        match serde_json::from_str::<GearList>(&items_json_string) {
-            Ok(gear_list) => Ok(gear_list),
-            Err(e) => {
-                eprintln!("Deserialization error: {}", e);
-                Err(format!("Failed to parse items JSON: {}", e))
-            }
-        }
+    pub fn find(&self, name: &str) -> Option<&Gear> {
+        self.items.iter().find(|gear| gear.name == name)
     }
 
-    pub fn get_gear_by_type(&self, gear_type: GearType) -> Vec<String> {
+    pub fn get_gear_by_type(&self, gear_type: GearType) -> Vec<GearOption> {
         self.items
             .iter()
             .filter(|gear| gear.gear_type == gear_type)
-            .map(|gear| gear.name.clone())
+            .map(GearOption::new)
             .collect()
     }
 
-    // Helper methods for getting specific gear types
-    pub fn helmets(&self) -> Vec<String> {
-        self.get_gear_by_type(GearType::Helmet)
+    /// Keys with a numeric value on at least one item, usable for sorting.
+    pub fn stat_keys(&self) -> Vec<String> {
+        let keys: BTreeSet<&String> = self
+            .items
+            .iter()
+            .flat_map(|gear| gear.stats.iter())
+            .filter(|(key, value)| value.is_number() && key.as_str() != "id")
+            .map(|(key, _)| key)
+            .collect();
+
+        BASE_STATS
+            .iter()
+            .map(|key| key.to_string())
+            .chain(keys.into_iter().cloned())
+            .collect()
     }
+}
 
-    pub fn chestplates(&self) -> Vec<String> {
-        self.get_gear_by_type(GearType::Chestplate)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Gear {
+    pub id: i64,
+    pub name: String,
+    pub tier: String,
+    #[serde(rename = "type")]
+    pub gear_type: GearType,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub lvl: i32,
+    #[serde(default, rename = "classReq", skip_serializing_if = "Option::is_none")]
+    pub class_req: Option<Class>,
+    #[serde(flatten)]
+    pub requirements: SkillPoints,
+    /// Base health, not an identification.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub hp: i32,
+    #[serde(flatten)]
+    pub defenses: Defenses,
+    /// Powder slots.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub slots: u8,
+    #[serde(default, rename = "majorIds", deserialize_with = "null_as_default")]
+    pub major_ids: Vec<String>,
+    /// Name of the set the item belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set: Option<String>,
+    /// Identifications don't roll, the listed value is always what you get.
+    #[serde(default, rename = "fixID", deserialize_with = "null_as_default")]
+    pub fixed_ids: bool,
+    #[serde(default, rename = "atkSpd", skip_serializing_if = "Option::is_none")]
+    pub attack_speed: Option<String>,
+    #[serde(flatten)]
+    pub damage: Damage,
+    /// Remaining item keys, mostly identifications like `sdPct` or `mr`.
+    #[serde(flatten)]
+    pub stats: BTreeMap<String, serde_json::Value>,
+}
+
+/// items.json sometimes writes `null` where a value is absent.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Class {
+    Warrior,
+    Assassin,
+    Mage,
+    Archer,
+    Shaman,
+}
+
+impl Class {
+    pub const ALL: [Class; 5] = [
+        Class::Warrior,
+        Class::Assassin,
+        Class::Mage,
+        Class::Archer,
+        Class::Shaman,
+    ];
+
+    /// The only weapon type the class can use.
+    pub fn weapon_type(self) -> GearType {
+        match self {
+            Class::Warrior => GearType::Spear,
+            Class::Assassin => GearType::Dagger,
+            Class::Mage => GearType::Wand,
+            Class::Archer => GearType::Bow,
+            Class::Shaman => GearType::Relik,
+        }
     }
 
-    pub fn leggings(&self) -> Vec<String> {
-        self.get_gear_by_type(GearType::Leggings)
+    /// Whether the class can use `gear`; items without a class requirement fit everyone.
+    pub fn can_use(self, gear: &Gear) -> bool {
+        if gear.gear_type.is_weapon() {
+            return gear.gear_type == self.weapon_type();
+        }
+        gear.class_req.is_none_or(|class| class == self)
     }
+}
 
-    pub fn boots(&self) -> Vec<String> {
-        self.get_gear_by_type(GearType::Boots)
+impl std::fmt::Display for Class {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
     }
+}
+
+/// Skill point requirements, named after items.json's `strReq`..`agiReq`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SkillPoints {
+    #[serde(default, rename = "strReq", deserialize_with = "null_as_default")]
+    pub strength: i32,
+    #[serde(default, rename = "dexReq", deserialize_with = "null_as_default")]
+    pub dexterity: i32,
+    #[serde(default, rename = "intReq", deserialize_with = "null_as_default")]
+    pub intelligence: i32,
+    #[serde(default, rename = "defReq", deserialize_with = "null_as_default")]
+    pub defense: i32,
+    #[serde(default, rename = "agiReq", deserialize_with = "null_as_default")]
+    pub agility: i32,
+}
 
-    pub fn rings(&self) -> Vec<String> {
-        self.get_gear_by_type(GearType::Ring)
+impl SkillPoints {
+    /// Label and value, in the game's order.
+    pub fn iter(&self) -> [(&'static str, i32); 5] {
+        [
+            ("Strength", self.strength),
+            ("Dexterity", self.dexterity),
+            ("Intelligence", self.intelligence),
+            ("Defense", self.defense),
+            ("Agility", self.agility),
+        ]
     }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct Defenses {
+    #[serde(default, rename = "eDef", deserialize_with = "null_as_default")]
+    pub earth: i32,
+    #[serde(default, rename = "tDef", deserialize_with = "null_as_default")]
+    pub thunder: i32,
+    #[serde(default, rename = "wDef", deserialize_with = "null_as_default")]
+    pub water: i32,
+    #[serde(default, rename = "fDef", deserialize_with = "null_as_default")]
+    pub fire: i32,
+    #[serde(default, rename = "aDef", deserialize_with = "null_as_default")]
+    pub air: i32,
+}
 
-    pub fn bracelets(&self) -> Vec<String> {
-        self.get_gear_by_type(GearType::Bracelet)
+impl Defenses {
+    pub fn iter(&self) -> [(&'static str, i32); 5] {
+        [
+            ("Earth", self.earth),
+            ("Thunder", self.thunder),
+            ("Water", self.water),
+            ("Fire", self.fire),
+            ("Air", self.air),
+        ]
     }
+}
+
+/// Weapon base damage ranges, e.g. `"45-60"`; `None` for armour and accessories.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct Damage {
+    #[serde(default, rename = "nDam", skip_serializing_if = "Option::is_none")]
+    pub neutral: Option<String>,
+    #[serde(default, rename = "eDam", skip_serializing_if = "Option::is_none")]
+    pub earth: Option<String>,
+    #[serde(default, rename = "tDam", skip_serializing_if = "Option::is_none")]
+    pub thunder: Option<String>,
+    #[serde(default, rename = "wDam", skip_serializing_if = "Option::is_none")]
+    pub water: Option<String>,
+    #[serde(default, rename = "fDam", skip_serializing_if = "Option::is_none")]
+    pub fire: Option<String>,
+    #[serde(default, rename = "aDam", skip_serializing_if = "Option::is_none")]
+    pub air: Option<String>,
+}
 
-    pub fn necklaces(&self) -> Vec<String> {
-        self.get_gear_by_type(GearType::Necklace)
+impl Damage {
+    /// Elements with a non-zero damage range.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("Neutral", &self.neutral),
+            ("Earth", &self.earth),
+            ("Thunder", &self.thunder),
+            ("Water", &self.water),
+            ("Fire", &self.fire),
+            ("Air", &self.air),
+        ]
+        .into_iter()
+        .filter_map(|(label, range)| Some((label, range.as_deref()?)))
+        .filter(|(_, range)| *range != "0-0")
     }
+}
 
-    pub fn weapons(&self) -> Vec<String> {
-        self.get_gear_by_type(GearType::Spear)
-            .into_iter()
-            .chain(self.get_gear_by_type(GearType::Wand))
-            .chain(self.get_gear_by_type(GearType::Bow))
-            .chain(self.get_gear_by_type(GearType::Dagger))
-            .chain(self.get_gear_by_type(GearType::Relik))
-            .collect()
+/// An identification as rolled on an item: the base value from items.json and,
+/// unless it's fixed, the range it can roll in (worst, best).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Identification {
+    pub info: &'static IdInfo,
+    pub base: i32,
+    pub range: Option<(i32, i32)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct IdInfo {
+    pub key: &'static str,
+    /// Name of the identification in the Wynncraft API's item database.
+    pub api_key: &'static str,
+    pub label: &'static str,
+    pub unit: &'static str,
+    /// Skill point bonuses never roll.
+    pub rolls: bool,
+}
+
+macro_rules! identifications {
+    ($($key:literal, $api_key:literal, $label:literal, $unit:literal, $rolls:literal;)*) => {
+        /// Identifications in the order the game lists them.
+        pub static IDENTIFICATIONS: &[IdInfo] = &[
+            $(IdInfo { key: $key, api_key: $api_key, label: $label, unit: $unit, rolls: $rolls }),*
+        ];
+    };
+}
+
+identifications! {
+    "str", "rawStrength", "Strength", "", false;
+    "dex", "rawDexterity", "Dexterity", "", false;
+    "int", "rawIntelligence", "Intelligence", "", false;
+    "def", "rawDefence", "Defense", "", false;
+    "agi", "rawAgility", "Agility", "", false;
+    "hpBonus", "rawHealth", "Health", "", true;
+    "hprRaw", "rawHealthRegen", "Health Regen", "", true;
+    "hprPct", "healthRegen", "Health Regen", "%", true;
+    "mr", "manaRegen", "Mana Regen", "/5s", true;
+    "ls", "lifeSteal", "Life Steal", "/3s", true;
+    "ms", "manaSteal", "Mana Steal", "/3s", true;
+    "spd", "walkSpeed", "Walk Speed", "%", true;
+    "sdRaw", "rawSpellDamage", "Spell Damage", "", true;
+    "sdPct", "spellDamage", "Spell Damage", "%", true;
+    "mdRaw", "rawMainAttackDamage", "Main Attack Damage", "", true;
+    "mdPct", "mainAttackDamage", "Main Attack Damage", "%", true;
+    "damRaw", "rawDamage", "Damage", "", true;
+    "damPct", "damage", "Damage", "%", true;
+    "nDamPct", "neutralDamage", "Neutral Damage", "%", true;
+    "eDamPct", "earthDamage", "Earth Damage", "%", true;
+    "tDamPct", "thunderDamage", "Thunder Damage", "%", true;
+    "wDamPct", "waterDamage", "Water Damage", "%", true;
+    "fDamPct", "fireDamage", "Fire Damage", "%", true;
+    "aDamPct", "airDamage", "Air Damage", "%", true;
+    "eDefPct", "earthDefence", "Earth Defense", "%", true;
+    "tDefPct", "thunderDefence", "Thunder Defense", "%", true;
+    "wDefPct", "waterDefence", "Water Defense", "%", true;
+    "fDefPct", "fireDefence", "Fire Defense", "%", true;
+    "aDefPct", "airDefence", "Air Defense", "%", true;
+    "atkTier", "rawAttackSpeed", "Attack Speed", " tier", true;
+    "critDamPct", "criticalDamageBonus", "Critical Damage", "%", true;
+    "poison", "poison", "Poison", "/3s", true;
+    "thorns", "thorns", "Thorns", "%", true;
+    "ref", "reflection", "Reflection", "%", true;
+    "expd", "exploding", "Exploding", "%", true;
+    "sprint", "sprint", "Sprint", "%", true;
+    "sprintReg", "sprintRegen", "Sprint Regen", "%", true;
+    "jh", "rawJumpHeight", "Jump Height", "", true;
+    "spRegen", "soulPointRegen", "Soul Point Regen", "%", true;
+    "eSteal", "stealing", "Stealing", "%", true;
+    "xpb", "xpBonus", "XP Bonus", "%", true;
+    "lb", "lootBonus", "Loot Bonus", "%", true;
+    "lq", "lootQuality", "Loot Quality", "%", true;
+    "gXp", "gatherXpBonus", "Gathering XP Bonus", "%", true;
+    "gSpd", "gatherSpeed", "Gathering Speed", "%", true;
+    "spPct1", "1stSpellCost", "1st Spell Cost", "%", true;
+    "spRaw1", "raw1stSpellCost", "1st Spell Cost", "", true;
+    "spPct2", "2ndSpellCost", "2nd Spell Cost", "%", true;
+    "spRaw2", "raw2ndSpellCost", "2nd Spell Cost", "", true;
+    "spPct3", "3rdSpellCost", "3rd Spell Cost", "%", true;
+    "spRaw3", "raw3rdSpellCost", "3rd Spell Cost", "", true;
+    "spPct4", "4thSpellCost", "4th Spell Cost", "%", true;
+    "spRaw4", "raw4thSpellCost", "4th Spell Cost", "", true;
+}
+
+impl IdInfo {
+    /// Spell costs are the only identifications where a negative roll helps.
+    pub fn lower_is_better(&self) -> bool {
+        self.key.starts_with("spPct") || self.key.starts_with("spRaw")
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct Gear {
-    id: i64,
-    name: String,
-    tier: String,
-    #[serde(rename = "type")]
-    gear_type: GearType,
+/// Numeric keys stored in typed fields rather than `Gear::stats`.
+const BASE_STATS: [&str; 13] = [
+    "lvl", "hp", "slots", "strReq", "dexReq", "intReq", "defReq", "agiReq", "eDef", "tDef", "wDef",
+    "fDef", "aDef",
+];
+
+/// Readable name of an items.json key, e.g. `hp` -> `Health` or `sdPct` -> `Spell Damage`.
+/// Keys the app doesn't know are returned as they are.
+pub fn stat_label(key: &str) -> &str {
+    match key {
+        "lvl" => "Combat Level",
+        "hp" => "Base Health",
+        "slots" => "Powder Slots",
+        "strReq" => "Strength Min",
+        "dexReq" => "Dexterity Min",
+        "intReq" => "Intelligence Min",
+        "defReq" => "Defense Min",
+        "agiReq" => "Agility Min",
+        "eDef" => "Earth Defense",
+        "tDef" => "Thunder Defense",
+        "wDef" => "Water Defense",
+        "fDef" => "Fire Defense",
+        "aDef" => "Air Defense",
+        _ => IDENTIFICATIONS
+            .iter()
+            .find(|info| info.key == key)
+            .map_or(key, |info| info.label),
+    }
 }
 
 impl Gear {
@@ -104,8 +627,62 @@ impl Gear {
             name: name.to_string(),
             tier: String::from("Common"),
             gear_type,
+            ..Default::default()
         }
     }
+
+    /// Numeric value of `key`, 0 when the item doesn't have it.
+    pub fn stat(&self, key: &str) -> f64 {
+        let base = match key {
+            "lvl" => self.lvl,
+            "hp" => self.hp,
+            "slots" => self.slots as i32,
+            "strReq" => self.requirements.strength,
+            "dexReq" => self.requirements.dexterity,
+            "intReq" => self.requirements.intelligence,
+            "defReq" => self.requirements.defense,
+            "agiReq" => self.requirements.agility,
+            "eDef" => self.defenses.earth,
+            "tDef" => self.defenses.thunder,
+            "wDef" => self.defenses.water,
+            "fDef" => self.defenses.fire,
+            "aDef" => self.defenses.air,
+            _ => {
+                return self
+                    .stats
+                    .get(key)
+                    .and_then(serde_json::Value::as_f64)
+                    .unwrap_or_default()
+            }
+        };
+        base as f64
+    }
+
+    /// The item's non-zero identifications with their roll ranges.
+    pub fn identifications(&self) -> Vec<Identification> {
+        IDENTIFICATIONS
+            .iter()
+            .filter_map(|info| {
+                let base = self.stats.get(info.key)?.as_i64()? as i32;
+                (base != 0).then(|| Identification {
+                    info,
+                    base,
+                    range: (info.rolls && !self.fixed_ids).then(|| roll_range(base)),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Worst and best roll of an identification: positive values roll 30% to 130%
+/// of the base, negative ones 130% to 70%.
+pub fn roll_range(base: i32) -> (i32, i32) {
+    let scaled = |pct: f64| (base as f64 * pct).round() as i32;
+    if base > 0 {
+        (scaled(0.3).max(1), scaled(1.3))
+    } else {
+        (scaled(1.3), scaled(0.7).min(-1))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -125,4 +702,279 @@ pub enum GearType {
     Relik,
     #[default]
     None,
-} 
\ No newline at end of file
+}
+
+impl GearType {
+    /// Weapon types in the order the weapon list shows them.
+    pub const WEAPONS: [GearType; 5] = [
+        GearType::Spear,
+        GearType::Wand,
+        GearType::Bow,
+        GearType::Dagger,
+        GearType::Relik,
+    ];
+
+    pub fn is_weapon(&self) -> bool {
+        matches!(
+            self,
+            GearType::Bow | GearType::Spear | GearType::Wand | GearType::Dagger | GearType::Relik
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::names;
+
+    fn items_with(slot: GearSlot, pool: &[&str]) -> Items {
+        let mut items = Items::default();
+        *slot.pool_mut(&mut items) = names(pool);
+        items
+    }
+
+    fn selections_for(items: &Items) -> GearSelections {
+        GearSelections::new(&GearList::default(), items, &GearFilter::default(), None)
+    }
+
+    #[test]
+    fn add_creates_empty_row_without_touching_pool() {
+        for slot in GearSlot::ALL {
+            let mut items = items_with(slot, &["A"]);
+            let mut gear = selections_for(&items);
+
+            gear.add(slot);
+            gear.apply_to(&mut items);
+
+            assert_eq!(gear.pool(slot).selections.len(), 2, "{:?}", slot);
+            assert_eq!(slot.pool(&items), &names(&["A"]), "{:?}", slot);
+        }
+    }
+
+    #[test]
+    fn select_fills_empty_row() {
+        for slot in GearSlot::ALL {
+            let mut items = Items::default();
+            let mut gear = selections_for(&items);
+
+            gear.add(slot);
+            gear.select(slot, 0, "A".to_string());
+            gear.apply_to(&mut items);
+
+            assert_eq!(slot.pool(&items), &names(&["A"]), "{:?}", slot);
+        }
+    }
+
+    #[test]
+    fn select_replaces_instead_of_accumulating() {
+        for slot in GearSlot::ALL {
+            let mut items = items_with(slot, &["A", "B"]);
+            let mut gear = selections_for(&items);
+
+            gear.select(slot, 1, "C".to_string());
+            gear.select(slot, 1, "D".to_string());
+            gear.apply_to(&mut items);
+
+            assert_eq!(slot.pool(&items), &names(&["A", "D"]), "{:?}", slot);
+        }
+    }
+
+    #[test]
+    fn select_out_of_range_is_ignored() {
+        for slot in GearSlot::ALL {
+            let mut items = items_with(slot, &["A"]);
+            let mut gear = selections_for(&items);
+
+            gear.select(slot, 5, "B".to_string());
+            gear.apply_to(&mut items);
+
+            assert_eq!(slot.pool(&items), &names(&["A"]), "{:?}", slot);
+        }
+    }
+
+    #[test]
+    fn remove_only_drops_the_given_row() {
+        for slot in GearSlot::ALL {
+            let mut items = items_with(slot, &["A", "B", "A"]);
+            let mut gear = selections_for(&items);
+
+            gear.remove(slot, 0);
+            gear.apply_to(&mut items);
+
+            assert_eq!(slot.pool(&items), &names(&["B", "A"]), "{:?}", slot);
+        }
+    }
+
+    #[test]
+    fn remove_empty_row_keeps_pool() {
+        for slot in GearSlot::ALL {
+            let mut items = items_with(slot, &["A"]);
+            let mut gear = selections_for(&items);
+
+            gear.add(slot);
+            gear.remove(slot, 1);
+            gear.remove(slot, 3);
+            gear.apply_to(&mut items);
+
+            assert_eq!(
+                gear.pool(slot).selections,
+                vec![Some("A".to_string())],
+                "{:?}",
+                slot
+            );
+            assert_eq!(slot.pool(&items), &names(&["A"]), "{:?}", slot);
+        }
+    }
+
+    #[test]
+    fn editing_one_slot_leaves_the_others_alone() {
+        for slot in GearSlot::ALL {
+            let mut items = Items::default();
+            for other in GearSlot::ALL {
+                *other.pool_mut(&mut items) = names(&["A"]);
+            }
+            let mut gear = selections_for(&items);
+
+            gear.select(slot, 0, "B".to_string());
+            gear.apply_to(&mut items);
+
+            for other in GearSlot::ALL.into_iter().filter(|other| *other != slot) {
+                assert_eq!(
+                    other.pool(&items),
+                    &names(&["A"]),
+                    "{:?} -> {:?}",
+                    slot,
+                    other
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn selections_round_trip_every_pool() {
+        let mut original = Items {
+            weapon: "Nirvana".to_string(),
+            ..Default::default()
+        };
+        for slot in GearSlot::ALL {
+            *slot.pool_mut(&mut original) = names(&[slot.singular(), "A", "A"]);
+        }
+        let mut items = Items::default();
+
+        selections_for(&original).apply_to(&mut items);
+
+        for slot in GearSlot::ALL {
+            assert_eq!(slot.pool(&items), slot.pool(&original), "{:?}", slot);
+        }
+        assert_eq!(items.weapon, original.weapon);
+    }
+
+    #[test]
+    fn weapon_selection_is_written_to_items() {
+        let mut items = Items::default();
+        let mut gear = selections_for(&items);
+
+        gear.selected_weapon = Some("Nirvana".to_string());
+        gear.apply_to(&mut items);
+
+        assert_eq!(items.weapon, "Nirvana");
+    }
+
+    #[test]
+    fn insert_skips_items_already_in_the_pool() {
+        for slot in GearSlot::ALL {
+            let mut items = items_with(slot, &["A"]);
+            let mut gear = selections_for(&items);
+
+            assert!(!gear.insert(slot, "A".to_string()), "{:?}", slot);
+            assert!(gear.insert(slot, "B".to_string()), "{:?}", slot);
+            gear.apply_to(&mut items);
+
+            assert_eq!(slot.pool(&items), &names(&["A", "B"]), "{:?}", slot);
+        }
+    }
+
+    #[test]
+    fn slots_map_to_their_gear_types() {
+        for slot in GearSlot::ALL {
+            assert_eq!(GearSlot::from_gear_type(&slot.gear_type()), Some(slot));
+        }
+        assert_eq!(GearSlot::from_gear_type(&GearType::Wand), None);
+    }
+
+    #[test]
+    fn pool_index_skips_empty_rows() {
+        let pool = GearPool {
+            selections: vec![None, Some("A".to_string()), None, Some("B".to_string())],
+            ..Default::default()
+        };
+
+        assert_eq!(pool.pool_index(0), None);
+        assert_eq!(pool.pool_index(1), Some(0));
+        assert_eq!(pool.pool_index(3), Some(1));
+        assert_eq!(pool.pool_index(4), None);
+    }
+
+    #[test]
+    fn items_json_entries_are_fully_parsed() {
+        let gear: Gear = serde_json::from_str(
+            r#"{
+                "id": 1234, "name": "Example Ring", "tier": "Rare", "type": "ring",
+                "lvl": 90, "classReq": "Mage", "intReq": 45, "agiReq": 0, "hp": 0,
+                "wDef": 60, "fDef": -30, "slots": null, "majorIds": null, "set": "Morph",
+                "sdPct": 10, "spd": -12, "int": 5, "drop": "NORMAL"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!((gear.lvl, gear.class_req), (90, Some(Class::Mage)));
+        assert_eq!(gear.requirements.intelligence, 45);
+        assert_eq!((gear.defenses.water, gear.defenses.fire), (60, -30));
+        assert_eq!(gear.set.as_deref(), Some("Morph"));
+        assert!(gear.major_ids.is_empty());
+        assert_eq!(gear.stat("wDef"), 60.0);
+        assert!(!gear.stats.contains_key("wDef"));
+
+        let ids = gear.identifications();
+        let keys: Vec<&str> = ids.iter().map(|id| id.info.key).collect();
+        assert_eq!(keys, vec!["int", "spd", "sdPct"]);
+        assert_eq!(ids[0].range, None);
+        assert_eq!(ids[1].range, Some((-16, -8)));
+        assert_eq!(ids[2].range, Some((3, 13)));
+    }
+
+    #[test]
+    fn fixed_items_do_not_roll() {
+        let mut gear = Gear::default_for_type(GearType::Ring, "Fixed", 1);
+        gear.fixed_ids = true;
+        gear.stats.insert("mr".to_string(), 4.into());
+
+        assert_eq!(gear.identifications()[0].range, None);
+    }
+
+    #[test]
+    fn class_limits_weapon_and_gear_options() {
+        let mut mage_ring = Gear::default_for_type(GearType::Ring, "Mage Ring", 2);
+        mage_ring.class_req = Some(Class::Mage);
+        let gear_list = GearList {
+            items: vec![
+                Gear::default_for_type(GearType::Bow, "Bow", 0),
+                Gear::default_for_type(GearType::Wand, "Wand", 1),
+                mage_ring,
+                Gear::default_for_type(GearType::Ring, "Any Ring", 3),
+            ],
+        };
+        let filter = GearFilter::new(&gear_list);
+
+        let gear = GearSelections::new(&gear_list, &Items::default(), &filter, Some(Class::Archer));
+
+        assert_eq!(gear.weapons.options(), [GearOption::new(&gear_list.items[0])]);
+        assert_eq!(
+            gear.pool(GearSlot::Ring).options.options(),
+            [GearOption::new(&gear_list.items[3])]
+        );
+        // Selections keep their option, and tier, even when they're filtered out
+        assert_eq!(gear.option(Some(&"Wand".to_string())).map(ToString::to_string).as_deref(), Some("Wand (Common)"));
+        assert_eq!(gear.option(Some(&"Missing".to_string())), None);
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeSet;
+
+use super::gear::Class;
+use super::{Gear, GearList};
+
+/// In-game tier order, used to sort the tier checkboxes.
+const TIER_ORDER: [&str; 7] = ["Normal", "Unique", "Rare", "Legendary", "Fabled", "Mythic", "Set"];
+
+/// Narrows the items offered in the gear pool and weapon lists. Only the
+/// options are filtered, items already in a pool stay there.
+#[derive(Debug, Clone, Default)]
+pub struct GearFilter {
+    /// Tiers present in items.json, lowest first.
+    pub tier_options: Vec<String>,
+    pub tiers: BTreeSet<String>,
+    /// Level bounds as typed; a bound that doesn't parse is ignored.
+    pub min_level: String,
+    pub max_level: String,
+    /// Hide items that require another class. Items without a class requirement always pass.
+    pub class: Option<Class>,
+}
+
+impl GearFilter {
+    pub fn new(gear_list: &GearList) -> Self {
+        let mut tier_options: Vec<String> = Vec::new();
+        for gear in &gear_list.items {
+            if !tier_options.contains(&gear.tier) {
+                tier_options.push(gear.tier.clone());
+            }
+        }
+        // Tiers the game doesn't know go last, in the order they were seen
+        tier_options.sort_by_key(|tier| {
+            TIER_ORDER
+                .iter()
+                .position(|known| known == tier)
+                .unwrap_or(TIER_ORDER.len())
+        });
+
+        Self {
+            tiers: tier_options.iter().cloned().collect(),
+            tier_options,
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self, gear: &Gear) -> bool {
+        let min_level = self.min_level.trim().parse().unwrap_or(i32::MIN);
+        let max_level = self.max_level.trim().parse().unwrap_or(i32::MAX);
+
+        self.tiers.contains(&gear.tier)
+            && (min_level..=max_level).contains(&gear.lvl)
+            && (self.class.is_none() || gear.class_req.is_none() || gear.class_req == self.class)
+    }
+
+    /// Whether any item could be hidden.
+    pub fn is_active(&self) -> bool {
+        self.tiers.len() < self.tier_options.len()
+            || !self.min_level.trim().is_empty()
+            || !self.max_level.trim().is_empty()
+            || self.class.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list};
+    use crate::config::GearType;
+
+    fn bow(name: &str, tier: &str, lvl: i32, class_req: Option<Class>) -> Gear {
+        Gear {
+            tier: tier.to_string(),
+            lvl,
+            class_req,
+            ..gear(GearType::Bow, name)
+        }
+    }
+
+    fn kept<'a>(filter: &GearFilter, gear_list: &'a GearList) -> Vec<&'a str> {
+        gear_list
+            .items
+            .iter()
+            .filter(|gear| filter.matches(gear))
+            .map(|gear| gear.name.as_str())
+            .collect()
+    }
+
+    fn items() -> GearList {
+        gear_list([
+            bow("Mythic Bow", "Mythic", 100, Some(Class::Archer)),
+            bow("Starter", "Normal", 1, None),
+            bow("Crafted", "Crafted", 50, None),
+            bow("Rare Wand", "Rare", 60, Some(Class::Mage)),
+            bow("Other Rare", "Rare", 70, None),
+        ])
+    }
+
+    #[test]
+    fn tiers_are_listed_in_game_order_once() {
+        let filter = GearFilter::new(&items());
+
+        assert_eq!(filter.tier_options, vec!["Normal", "Rare", "Mythic", "Crafted"]);
+        assert!(!filter.is_active());
+    }
+
+    #[test]
+    fn empty_items_json_filters_nothing() {
+        let filter = GearFilter::new(&GearList::default());
+
+        assert!(filter.tier_options.is_empty());
+        assert!(!filter.is_active());
+    }
+
+    #[test]
+    fn filters_combine() {
+        let items = items();
+        let mut filter = GearFilter::new(&items);
+        filter.min_level = " 50 ".to_string();
+        filter.class = Some(Class::Archer);
+        filter.tiers.remove("Crafted");
+
+        assert_eq!(kept(&filter, &items), vec!["Mythic Bow", "Other Rare"]);
+        assert!(filter.is_active());
+
+        filter.max_level = "40".to_string();
+        assert!(kept(&filter, &items).is_empty());
+    }
+
+    #[test]
+    fn unparsable_levels_are_ignored() {
+        let items = items();
+        let mut filter = GearFilter::new(&items);
+        for bound in ["abc", "99999999999", "-"] {
+            filter.min_level = bound.to_string();
+            filter.max_level = bound.to_string();
+            assert_eq!(kept(&filter, &items).len(), items.items.len(), "{}", bound);
+        }
+    }
+
+    #[test]
+    fn classless_items_pass_any_class() {
+        let items = items();
+        let mut filter = GearFilter::new(&items);
+        filter.class = Some(Class::Warrior);
+
+        assert_eq!(kept(&filter, &items), vec!["Starter", "Crafted", "Other Rare"]);
+    }
+}
@@ -0,0 +1,134 @@
+use iced::{Color, Element, Length};
+use iced_widget::{column, container, text, Column, Space};
+
+use super::gear::{Gear, Identification};
+use super::style::*;
+
+// In-game text colours
+const GRAY: Color = Color::from_rgb(0.67, 0.67, 0.67);
+const GOOD: Color = Color::from_rgb(0.33, 1.0, 0.33);
+const BAD: Color = Color::from_rgb(1.0, 0.33, 0.33);
+const MAJOR_ID: Color = Color::from_rgb(0.33, 1.0, 1.0);
+const TOOLTIP_WIDTH: f32 = 320.0;
+
+/// Renders an item the way the game's tooltip does: tier-coloured name, base
+/// stats, requirements, identifications with their roll ranges, powder slots,
+/// major IDs and set.
+pub fn item_tooltip<'a, Message: 'a>(gear: &'a Gear) -> Element<'a, Message> {
+    let mut lines = column![text(&gear.name).size(SUBHEAD).color(tier_color(&gear.tier))].spacing(2);
+
+    // === Base stats ===
+    let mut base = Vec::new();
+    if let Some(speed) = &gear.attack_speed {
+        base.push(line(format!("{} Attack Speed", title_case(speed))).color(GRAY).into());
+    }
+    for (element, range) in gear.damage.iter() {
+        base.push(line(format!("{} Damage: {}", element, range)).into());
+    }
+    if gear.hp != 0 {
+        base.push(line(format!("Health: {}", signed(gear.hp))).into());
+    }
+    for (element, defense) in gear.defenses.iter().into_iter().filter(|(_, value)| *value != 0) {
+        base.push(line(format!("{} Defense: {}", element, signed(defense))).into());
+    }
+    lines = push_section(lines, base);
+
+    // === Requirements ===
+    let mut requirements = Vec::new();
+    if let Some(class) = gear.class_req {
+        requirements.push(line(format!("Class Req: {}", class)).color(GRAY).into());
+    }
+    if gear.lvl > 0 {
+        requirements.push(line(format!("Combat Lv. Min: {}", gear.lvl)).color(GRAY).into());
+    }
+    for (skill, points) in gear.requirements.iter().into_iter().filter(|(_, value)| *value != 0) {
+        requirements.push(line(format!("{} Min: {}", skill, points)).color(GRAY).into());
+    }
+    lines = push_section(lines, requirements);
+
+    // === Identifications ===
+    let identifications = gear.identifications().into_iter().map(identification).collect();
+    lines = push_section(lines, identifications);
+
+    // === Powders, major IDs, set ===
+    let mut extras = Vec::new();
+    if gear.slots > 0 {
+        extras.push(line(format!("[0/{}] Powder Slots", gear.slots)).color(GRAY).into());
+    }
+    for major_id in &gear.major_ids {
+        extras.push(line(format!("Major ID: {}", major_id)).color(MAJOR_ID).into());
+    }
+    if let Some(set) = &gear.set {
+        extras.push(line(format!("Set: {}", set)).color(GOOD).into());
+    }
+    extras.push(line(format!("{} Item", gear.tier)).color(tier_color(&gear.tier)).into());
+    lines = push_section(lines, extras);
+
+    container(lines)
+        .padding(SPACE)
+        .width(Length::Fixed(TOOLTIP_WIDTH))
+        .style(|_| container::Style {
+            background: Some(Color::from_rgb(0.06, 0.0, 0.1).into()),
+            border: iced::Border {
+                color: Color::from_rgb(0.2, 0.0, 0.4),
+                width: 2.0,
+                radius: 4.0.into(),
+            },
+            text_color: Some(Color::WHITE),
+            ..Default::default()
+        })
+        .into()
+}
+
+/// `+3% to +13% Spell Damage`, coloured by whether the roll helps.
+fn identification<'a, Message: 'a>(id: Identification) -> Element<'a, Message> {
+    let value = match id.range {
+        Some((worst, best)) => format!("{}{} to {}{}", signed(worst), id.info.unit, signed(best), id.info.unit),
+        None => format!("{}{}", signed(id.base), id.info.unit),
+    };
+    let good = (id.base > 0) != id.info.lower_is_better();
+
+    line(format!("{} {}", value, id.info.label))
+        .color(if good { GOOD } else { BAD })
+        .into()
+}
+
+fn line<'a>(content: String) -> iced_widget::Text<'a> {
+    text(content).size(TEXT)
+}
+
+/// Adds a group of lines after a blank line, skipping empty groups.
+fn push_section<'a, Message: 'a>(
+    lines: Column<'a, Message>,
+    section: Vec<Element<'a, Message>>,
+) -> Column<'a, Message> {
+    if section.is_empty() {
+        return lines;
+    }
+    lines
+        .push(Space::with_height(SPACE))
+        .push(Column::with_children(section).spacing(2))
+}
+
+fn signed(value: i32) -> String {
+    if value > 0 {
+        format!("+{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// `VERY_FAST` -> `Very Fast`
+fn title_case(value: &str) -> String {
+    value
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -1,22 +1,295 @@
+mod bulk_import;
+pub mod bundle;
+pub mod config_diff;
+pub mod estimate;
+mod fill_pools;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod presets;
+pub mod sets;
+pub mod skill_points;
 mod gear;
+pub mod gear_filter;
+pub mod item_view;
 mod config_view;
 mod config_update;
 pub mod style;
+pub mod thresholds;
+pub mod validation;
 
-pub use gear::{Gear, GearList, GearSelections, GearType};
-use crate::build_config::Config;
+pub use fill_pools::FillPools;
+pub use gear::{stat_label, Class, Damage, Defenses, Gear, GearList, GearOption, GearSelections, GearSlot, GearType, SkillPoints, IDENTIFICATIONS};
+pub use thresholds::ThresholdSection;
+pub use validation::ConfigErrors;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
+
+use bulk_import::ImportReport;
+use bundle::PendingImport;
+use config_diff::FieldDiff;
+use estimate::Estimate;
+use gear_filter::GearFilter;
+use presets::Preset;
+use sets::{SetList, SetSummary};
+use skill_points::Allocation;
+use iced_widget::text_editor;
+
+use crate::build_config::{self, Config, Items};
+use crate::item_changelog::Changelog;
+use crate::item_db::ItemDb;
+use crate::migrations;
+
+pub const CONFIG_PATH: &str = "config/config.toml";
+
+/// Another config.toml loaded for side-by-side comparison with the current one.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub path: String,
+    pub other: Config,
+    pub diff: Vec<FieldDiff>,
+    /// Field waiting for confirmation before it's written into the other file.
+    pub pending_write: Option<String>,
+}
 
 #[derive(Default)]
 pub struct ConfigFile {
     pub error_message: Option<String>,
+    /// Why config.toml couldn't be loaded. Saving is disabled while set so the
+    /// broken file isn't overwritten with defaults.
+    pub load_error: Option<String>,
+    pub load_error_dismissed: bool,
     pub gear: GearSelections,
+    pub gear_list: GearList,
     pub config: Config,
+    pub errors: ConfigErrors,
+    pub api_testing: bool,
+    pub api_status: Option<Result<String, String>>,
+    /// An item database download is in progress.
+    pub items_updating: bool,
+    pub items_update_status: Option<Result<String, String>>,
+    /// Threshold sections toggled on in the UI that have no values yet.
+    pub enabled_thresholds: BTreeSet<ThresholdSection>,
+    /// Values of threshold sections toggled off, put back when they're toggled on again.
+    pub disabled_thresholds: BTreeMap<ThresholdSection, toml::Table>,
+    /// Numeric input that doesn't parse yet (e.g. a lone `-`), keyed by field path.
+    /// Player and hppeng drafts are reported as validation errors until fixed.
+    pub drafts: BTreeMap<String, String>,
+    pub bulk_import: text_editor::Content,
+    pub import_report: Option<ImportReport>,
+    pub fill_pools: FillPools,
+    /// Narrows the gear pool and weapon options.
+    pub gear_filter: GearFilter,
+    pub estimate: Estimate,
+    /// Pools `estimate` was counted for; counting visits up to 2^16 subsets of the
+    /// illegal combinations, so it only reruns when they change.
+    pub estimate_input: Option<Items>,
+    /// Combinations per second typed by the user; overrides the measured rate.
+    pub combos_per_second: String,
+    /// Rate observed over the last builder run that finished.
+    pub measured_rate: Option<f64>,
+    /// When the running builder started and how many combinations it was given.
+    pub run_started: Option<(Instant, u128)>,
+    pub presets: Vec<Preset>,
+    pub selected_preset: Option<Preset>,
+    /// Name to save the current settings under.
+    pub preset_name: String,
+    pub preset_status: Option<Result<String, String>>,
+    /// File exported to and imported from.
+    pub bundle_path: String,
+    /// Export the items.json entries the config references along with it.
+    pub bundle_include_items: bool,
+    /// Exported config string, or one pasted for import.
+    pub bundle_text: text_editor::Content,
+    pub pending_import: Option<PendingImport>,
+    pub bundle_status: Option<Result<String, String>>,
+    /// Path of the config.toml to compare against.
+    pub compare_path: String,
+    pub comparison: Option<Comparison>,
+    pub compare_status: Option<Result<String, String>>,
+    /// Show a TOML editor for the whole config next to the form.
+    pub raw_mode: bool,
+    pub raw_text: text_editor::Content,
+    /// Why the editor text can't be used, shown under the editor.
+    pub raw_error: Option<String>,
+    /// The config as TOML when `raw_text` was last in sync with it.
+    pub raw_synced: String,
+    /// Item shown in the detail panel, last hovered or picked in a gear list.
+    pub inspected_item: Option<String>,
+    /// items.json is still being read in the background; item checks wait for it.
+    pub items_loading: bool,
+    /// Game version items.json was exported for, if it says.
+    pub game_version: Option<String>,
+    pub items_from_cache: bool,
+    /// Why the item cache couldn't be used on the last load.
+    pub items_cache_error: Option<String>,
+    /// Items picked per `skill_points::EQUIP_SLOTS` entry to check if they can be worn together.
+    pub skill_point_items: [Option<String>; 8],
+    /// Allocation for `skill_point_items` and the weapon, once an item is picked.
+    pub skill_points: Option<Result<Allocation, String>>,
+    /// Picks and weapon `skill_points` was solved for; solving tries up to 8! orders,
+    /// so it only reruns when these change.
+    pub skill_points_input: Option<([Option<String>; 8], String)>,
+    /// What the last change to items.json added, removed and re-statted.
+    pub item_changelog: Option<Changelog>,
+    /// Set definitions from items.json, empty when it has none.
+    pub sets: SetList,
+    /// Sets with pieces in the pools, recomputed on validation.
+    pub set_summaries: Vec<SetSummary>,
 }
 
 impl ConfigFile {
     pub fn save_config(&mut self) {
-        self.config
-            .save_config("config/config.toml")
-            .unwrap_or_default();
+        if self.load_error.is_some() {
+            return;
+        }
+        self.config.save_config(CONFIG_PATH).unwrap_or_default();
+    }
+
+    /// Loads config.toml again, e.g. after it was fixed by hand.
+    pub fn reload_config(&mut self) {
+        match build_config::load_config(CONFIG_PATH) {
+            Ok(config) => {
+                self.gear = GearSelections::new(&self.gear_list, &config.items, &self.gear_filter, config.player.class);
+                self.config = config;
+                self.load_error = None;
+                self.load_error_dismissed = false;
+                self.enabled_thresholds.clear();
+                self.disabled_thresholds.clear();
+                self.drafts.clear();
+            }
+            Err(e) => self.load_error = Some(e),
+        }
+    }
+
+    /// Sets the unreadable config.toml aside and starts over from the current settings.
+    pub fn start_fresh(&mut self) {
+        match migrations::backup(CONFIG_PATH, "broken") {
+            Ok(_) => {
+                self.load_error = None;
+                self.save_config();
+            }
+            Err(e) => self.load_error = Some(e),
+        }
+    }
+
+    /// Takes the items loaded in the background. Without items.json each list gets a
+    /// placeholder entry so the missing file is obvious.
+    pub fn items_loaded(&mut self, result: Result<ItemDb, String>) {
+        match result {
+            Ok(item_db) => {
+                self.gear_list = item_db.gear_list;
+                self.game_version = item_db.game_version;
+                self.items_from_cache = item_db.from_cache;
+                self.items_cache_error = item_db.cache_error;
+                self.sets = item_db.sets;
+                // A re-saved items.json with the same items isn't worth a notice
+                if let Some(changelog) = item_db.changelog.filter(|changelog| !changelog.is_empty()) {
+                    self.item_changelog = Some(changelog);
+                }
+            }
+            Err(e) => {
+                self.gear_list = GearList {
+                    items: vec![
+                        Gear::default_for_type(GearType::Helmet, "No helmets found", -1),
+                        Gear::default_for_type(GearType::Chestplate, "No chestplates found", -2),
+                        Gear::default_for_type(GearType::Leggings, "No leggings found", -3),
+                        Gear::default_for_type(GearType::Boots, "No boots found", -4),
+                        Gear::default_for_type(GearType::Ring, "No rings found", -5),
+                        Gear::default_for_type(GearType::Bracelet, "No bracelets found", -6),
+                        Gear::default_for_type(GearType::Necklace, "No necklaces found", -7),
+                        Gear::default_for_type(GearType::Spear, "No weapons found", -8),
+                    ],
+                };
+                self.error_message = Some(format!("Error loading items.json: {}", e));
+            }
+        }
+
+        self.items_loading = false;
+        self.skill_points_input = None;
+        self.gear_filter = GearFilter::new(&self.gear_list);
+        self.gear.apply_filter(&self.gear_list, &self.gear_filter, self.config.player.class);
+        self.fill_pools = FillPools::new(&self.gear_list);
+        self.validate();
+    }
+
+    pub fn is_threshold_enabled(&self, section: ThresholdSection) -> bool {
+        self.enabled_thresholds.contains(&section) || section.is_set(&self.config)
+    }
+
+    /// Rewrites the TOML editor from the config if the config changed since they were last in sync.
+    pub fn sync_raw_text(&mut self) {
+        if !self.raw_mode {
+            return;
+        }
+        let toml_string = toml::to_string(&self.config).unwrap_or_default();
+        if toml_string != self.raw_synced {
+            self.raw_text = text_editor::Content::with_text(&toml_string);
+            self.raw_synced = toml_string;
+            self.raw_error = None;
+        }
     }
-} 
\ No newline at end of file
+
+    /// Recomputes the comparison after either side changed.
+    pub fn refresh_comparison(&mut self) {
+        if let Some(comparison) = &mut self.comparison {
+            comparison.diff = config_diff::diff(&self.config, &comparison.other);
+        }
+    }
+
+    pub fn validate(&mut self) {
+        let gear_list = (!self.items_loading).then_some(&self.gear_list);
+        self.errors = validation::validate(&self.config, gear_list, &self.drafts);
+        if self.estimate_input.as_ref() != Some(&self.config.items) {
+            self.estimate = estimate::combinations(&self.config.items);
+            self.estimate_input = Some(self.config.items.clone());
+        }
+        self.refresh_skill_points();
+        self.set_summaries = sets::summarize(&self.sets, &self.gear_list, &self.config.items);
+    }
+
+    /// Solves the skill points again if the picks or the weapon changed since the last solve.
+    fn refresh_skill_points(&mut self) {
+        let skill_points_input = (self.skill_point_items.clone(), self.config.items.weapon.clone());
+        if self.skill_points_input.as_ref() != Some(&skill_points_input) {
+            self.skill_points = self.solve_skill_points();
+            self.skill_points_input = Some(skill_points_input);
+        }
+    }
+
+    /// Picked items that are still in items.json, plus the config's weapon.
+    fn solve_skill_points(&self) -> Option<Result<Allocation, String>> {
+        let armour: Vec<&Gear> = self
+            .skill_point_items
+            .iter()
+            .flatten()
+            .filter_map(|name| self.gear_list.find(name))
+            .collect();
+        if armour.is_empty() {
+            return None;
+        }
+        let weapon = self.gear_list.find(&self.config.items.weapon);
+        Some(skill_points::solve(&armour, weapon))
+    }
+
+    /// Combinations per second used for the runtime estimate.
+    pub fn combos_rate(&self) -> Option<f64> {
+        self.combos_per_second
+            .parse()
+            .ok()
+            .filter(|rate: &f64| *rate > 0.0)
+            .or(self.measured_rate)
+    }
+
+    pub fn builder_started(&mut self) {
+        self.run_started = Some((Instant::now(), self.estimate.combinations));
+    }
+
+    pub fn builder_finished(&mut self) {
+        if let Some((started, combinations)) = self.run_started.take() {
+            let elapsed = started.elapsed().as_secs_f64();
+            if elapsed > 0.0 && combinations > 0 {
+                self.measured_rate = Some(combinations as f64 / elapsed);
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::build_config::Config;
+
+use super::ThresholdSection;
+
+/// User presets live here, one TOML file per preset.
+pub const PRESETS_DIR: &str = "settings/presets";
+
+const BUILT_IN: [&str; 3] = [
+    r#"
+name = "Spell-spam Mage"
+description = "Level cap, high mana regen and spell damage"
+
+[player]
+lvl = 106
+available_point = 200
+
+[threshold_first]
+min_hp = 9000
+
+[threshold_second]
+min_mr = 10
+min_sd_pct = 60
+"#,
+    r#"
+name = "Tanky Warrior"
+description = "Level cap, large health pool with sustain"
+
+[player]
+lvl = 106
+available_point = 200
+
+[threshold_first]
+min_hp = 18000
+
+[threshold_second]
+min_hpr = 600
+min_ls = 300
+
+[threshold_fifth]
+min_ehp = 150000
+"#,
+    r#"
+name = "Speed Assassin"
+description = "Level cap, fast movement with damage"
+
+[player]
+lvl = 106
+available_point = 200
+
+[threshold_first]
+min_hp = 10000
+
+[threshold_second]
+min_spd = 40
+min_ms = 5
+"#,
+];
+
+/// How a preset combines with the config it's applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetMode {
+    /// Preset values overwrite matching keys; everything else is kept.
+    Merge,
+    /// Threshold sections become exactly the preset's; player keys it sets are overwritten.
+    Replace,
+}
+
+/// Saved player and threshold settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// `player` and `threshold_*` tables, as they appear in `config.toml`.
+    #[serde(flatten)]
+    pub sections: toml::Table,
+    /// File the preset was loaded from, `None` for built-in presets.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_some() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{} (built-in)", self.name)
+        }
+    }
+}
+
+impl PartialEq for Preset {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.path == other.path
+    }
+}
+
+impl Preset {
+    /// Captures the player and threshold settings of `config`.
+    pub fn from_config(name: &str, config: &Config) -> Result<Self, String> {
+        let mut sections = toml::Table::new();
+        let player = toml::Table::try_from(&config.player).map_err(|e| e.to_string())?;
+        sections.insert("player".to_string(), toml::Value::Table(player));
+        for section in ThresholdSection::ALL {
+            if let Some(table) = section.table(config) {
+                sections.insert(section.key().to_string(), toml::Value::Table(table));
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            description: String::new(),
+            sections,
+            path: None,
+        })
+    }
+
+    /// Returns `config` with the preset applied, leaving it untouched if the preset is invalid.
+    pub fn apply(&self, config: &Config, mode: PresetMode) -> Result<Config, String> {
+        let mut config = config.clone();
+
+        if let Some(player) = self.section("player") {
+            let mut table = toml::Table::try_from(&config.player).map_err(|e| e.to_string())?;
+            table.extend(player.clone());
+            config.player = table
+                .try_into()
+                .map_err(|e: toml::de::Error| format!("Invalid player settings: {}", e))?;
+        }
+
+        for section in ThresholdSection::ALL {
+            let preset = self.section(section.key());
+            let table = match (mode, preset) {
+                (PresetMode::Replace, preset) => preset.cloned(),
+                (PresetMode::Merge, Some(preset)) => {
+                    let mut table = section.table(&config).unwrap_or_default();
+                    table.extend(preset.clone());
+                    Some(table)
+                }
+                (PresetMode::Merge, None) => continue,
+            };
+            section
+                .set_table(&mut config, table)
+                .map_err(|e| format!("Invalid {}: {}", section.key(), e))?;
+        }
+
+        Ok(config)
+    }
+
+    fn section(&self, key: &str) -> Option<&toml::Table> {
+        self.sections.get(key).and_then(toml::Value::as_table)
+    }
+}
+
+/// Built-in presets followed by the ones in `PRESETS_DIR`, plus any files that failed to load.
+pub fn load_presets() -> (Vec<Preset>, Vec<String>) {
+    let mut presets: Vec<Preset> = BUILT_IN
+        .iter()
+        .map(|preset| toml::from_str(preset).expect("built-in presets are valid TOML"))
+        .collect();
+    let mut errors = Vec::new();
+
+    let Ok(entries) = std::fs::read_dir(PRESETS_DIR) else {
+        return (presets, errors);
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match load_preset(&path) {
+            Ok(preset) => presets.push(preset),
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    (presets, errors)
+}
+
+fn load_preset(path: &Path) -> Result<Preset, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut preset: Preset = toml::from_str(&content).map_err(|e| e.to_string())?;
+    preset.path = Some(path.to_path_buf());
+    Ok(preset)
+}
+
+/// Writes the preset to `PRESETS_DIR`, named after the preset, and returns where it went.
+pub fn save_preset(preset: &Preset) -> Result<PathBuf, String> {
+    let file_name: String = preset
+        .name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if file_name.is_empty() {
+        return Err("Enter a name for the preset".to_string());
+    }
+
+    std::fs::create_dir_all(PRESETS_DIR).map_err(|e| e.to_string())?;
+    let path = Path::new(PRESETS_DIR).join(format!("{}.toml", file_name));
+    let toml_string = toml::to_string(preset).map_err(|e| e.to_string())?;
+    std::fs::write(&path, toml_string).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::thresholds::field;
+
+    fn preset(toml: &str) -> Preset {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn config_with_thresholds() -> Config {
+        let mut config = Config::default();
+        config.player.lvl = 80;
+        config.player.base_hp = 500;
+        field(ThresholdSection::First, "min_hp").unwrap().set(&mut config, Some(5000)).unwrap();
+        field(ThresholdSection::Second, "min_mr").unwrap().set(&mut config, Some(3)).unwrap();
+        config
+    }
+
+    #[test]
+    fn built_in_presets_parse() {
+        for toml in BUILT_IN {
+            preset(toml).apply(&Config::default(), PresetMode::Replace).unwrap();
+        }
+    }
+
+    #[test]
+    fn merge_keeps_unrelated_values() {
+        let preset = preset("name = \"x\"\n[player]\nlvl = 106\n[threshold_second]\nmin_sd_pct = 40\n");
+
+        let config = preset.apply(&config_with_thresholds(), PresetMode::Merge).unwrap();
+
+        assert_eq!(config.player.lvl, 106);
+        assert_eq!(config.player.base_hp, 500);
+        assert_eq!(config.threshold_first.and_then(|first| first.min_hp), Some(5000));
+        let second = config.threshold_second.unwrap();
+        assert_eq!((second.min_mr, second.min_sd_pct), (Some(3), Some(40)));
+    }
+
+    #[test]
+    fn replace_drops_sections_the_preset_lacks() {
+        let preset = preset("name = \"x\"\n[threshold_second]\nmin_sd_pct = 40\n");
+
+        let config = preset.apply(&config_with_thresholds(), PresetMode::Replace).unwrap();
+
+        assert!(config.threshold_first.is_none());
+        let second = config.threshold_second.unwrap();
+        assert_eq!((second.min_mr, second.min_sd_pct), (None, Some(40)));
+    }
+
+    #[test]
+    fn presets_round_trip_through_toml() {
+        let preset = Preset::from_config("Mine", &config_with_thresholds()).unwrap();
+        let parsed: Preset = toml::from_str(&toml::to_string(&preset).unwrap()).unwrap();
+
+        assert_eq!(parsed.sections, preset.sections);
+    }
+}
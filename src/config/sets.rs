@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::build_config::Items;
+use super::{stat_label, Gear, GearList, IDENTIFICATIONS};
+
+/// Non-identification stats that add up across a set.
+const BASE_TOTALS: [&str; 6] = ["hp", "eDef", "tDef", "wDef", "fDef", "aDef"];
+
+/// One entry of items.json's `sets`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ItemSet {
+    pub items: Vec<String>,
+    /// `bonuses[n - 1]` applies while `n` pieces are worn, keyed like items.json stats.
+    #[serde(default)]
+    pub bonuses: Vec<BTreeMap<String, serde_json::Value>>,
+}
+
+impl ItemSet {
+    /// Numeric bonus for `key` with `pieces` worn, 0 when that count gives none.
+    pub fn bonus(&self, pieces: usize, key: &str) -> f64 {
+        pieces
+            .checked_sub(1)
+            .and_then(|idx| self.bonuses.get(idx))
+            .and_then(|bonus| bonus.get(key))
+            .and_then(serde_json::Value::as_f64)
+            .unwrap_or_default()
+    }
+}
+
+/// Set definitions by set name, from the `sets` object WynnBuilder's items.json has
+/// next to `items`. Item lists without one (like the game API's) have no sets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SetList {
+    pub sets: BTreeMap<String, ItemSet>,
+}
+
+impl SetList {
+    /// Name of the set `gear` belongs to, from the set definitions or else the item's own `set` key.
+    pub fn set_of<'a>(&'a self, gear: &'a Gear) -> Option<&'a str> {
+        self.sets
+            .iter()
+            .find(|(_, set)| set.items.contains(&gear.name))
+            .map(|(name, _)| name.as_str())
+            .or(gear.set.as_deref())
+    }
+}
+
+/// A set with pieces in the gear pools and what wearing all of those pieces gives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetSummary {
+    pub name: String,
+    /// Pooled pieces, in the set's order.
+    pub pieces: Vec<String>,
+    pub size: usize,
+    /// Stats of the pooled pieces plus the bonus for that many pieces, as label and value.
+    pub totals: Vec<(String, i64)>,
+}
+
+impl SetSummary {
+    pub fn is_full(&self) -> bool {
+        self.pieces.len() == self.size
+    }
+}
+
+/// Every set with a piece in the pools, full sets first, then by pieces pooled.
+pub fn summarize(sets: &SetList, gear_list: &GearList, items: &Items) -> Vec<SetSummary> {
+    let pooled: Vec<&String> = items.pooled().collect();
+    let mut summaries: Vec<SetSummary> = sets
+        .sets
+        .iter()
+        .filter_map(|(name, set)| {
+            let pieces: Vec<&Gear> = set
+                .items
+                .iter()
+                .filter(|item| pooled.contains(item))
+                .filter_map(|item| gear_list.find(item))
+                .collect();
+            if pieces.is_empty() {
+                return None;
+            }
+            Some(SetSummary {
+                name: name.clone(),
+                pieces: pieces.iter().map(|gear| gear.name.clone()).collect(),
+                size: set.items.len(),
+                totals: totals(set, &pieces),
+            })
+        })
+        .collect();
+
+    summaries.sort_by(|a, b| {
+        b.is_full()
+            .cmp(&a.is_full())
+            .then(b.pieces.len().cmp(&a.pieces.len()))
+            .then(a.name.cmp(&b.name))
+    });
+    summaries
+}
+
+/// Base stats and identifications first, then bonus-only keys; zeros are left out.
+fn totals(set: &ItemSet, pieces: &[&Gear]) -> Vec<(String, i64)> {
+    let mut keys: Vec<&str> = BASE_TOTALS
+        .into_iter()
+        .chain(IDENTIFICATIONS.iter().map(|info| info.key))
+        .collect();
+    if let Some(bonus) = pieces.len().checked_sub(1).and_then(|idx| set.bonuses.get(idx)) {
+        for key in bonus.keys() {
+            if !keys.contains(&key.as_str()) {
+                keys.push(key);
+            }
+        }
+    }
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let total: f64 = pieces.iter().map(|gear| gear.stat(key)).sum::<f64>() + set.bonus(pieces.len(), key);
+            let total = total.round() as i64;
+            (total != 0).then(|| (stat_label(key).to_string(), total))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list, names};
+    use crate::config::GearType;
+
+    const SETS_JSON: &str = r#"{
+        "Leaf": {
+            "items": ["Leaf Cap", "Leaf Tunic", "Leaf Pants"],
+            "bonuses": [{}, {"hp": 20}, {"hp": 50, "sdPct": 10, "note": "text"}]
+        },
+        "Ghost": {
+            "items": ["Ghost Cap", "Ghost Boots"]
+        }
+    }"#;
+
+    fn sets() -> SetList {
+        serde_json::from_str(SETS_JSON).unwrap()
+    }
+
+    fn items() -> GearList {
+        gear_list([
+            Gear { hp: 10, ..gear(GearType::Helmet, "Leaf Cap") },
+            Gear { hp: 20, ..gear(GearType::Chestplate, "Leaf Tunic") },
+            Gear { hp: 15, ..gear(GearType::Leggings, "Leaf Pants") },
+            Gear { hp: 5, ..gear(GearType::Helmet, "Ghost Cap") },
+        ])
+    }
+
+    #[test]
+    fn full_sets_come_first_with_their_bonus() {
+        let pools = Items {
+            helmets: names(&["Ghost Cap", "Leaf Cap"]),
+            chest_plates: names(&["Leaf Tunic"]),
+            leggings: names(&["Leaf Pants"]),
+            ..Default::default()
+        };
+
+        let summaries = summarize(&sets(), &items(), &pools);
+
+        assert_eq!(summaries.iter().map(|set| set.name.as_str()).collect::<Vec<_>>(), vec!["Leaf", "Ghost"]);
+        assert!(summaries[0].is_full());
+        // Non-numeric bonuses are skipped
+        assert_eq!(summaries[0].totals, vec![("Base Health".to_string(), 95), ("Spell Damage".to_string(), 10)]);
+        assert!(!summaries[1].is_full());
+        assert_eq!(summaries[1].totals, vec![("Base Health".to_string(), 5)]);
+    }
+
+    #[test]
+    fn partial_sets_use_the_bonus_for_their_piece_count() {
+        let pools = Items {
+            helmets: names(&["Leaf Cap"]),
+            chest_plates: names(&["Leaf Tunic"]),
+            ..Default::default()
+        };
+
+        let summaries = summarize(&sets(), &items(), &pools);
+
+        assert_eq!(summaries[0].pieces, names(&["Leaf Cap", "Leaf Tunic"]));
+        assert_eq!(summaries[0].totals, vec![("Base Health".to_string(), 50)]);
+    }
+
+    #[test]
+    fn pieces_missing_from_items_json_are_skipped() {
+        // "Ghost Boots" is in the set and pooled, but not in items.json
+        let pools = Items {
+            boots: names(&["Ghost Boots"]),
+            ..Default::default()
+        };
+        assert!(summarize(&sets(), &items(), &pools).is_empty());
+
+        let empty_pools = Items::default();
+        assert!(summarize(&sets(), &items(), &empty_pools).is_empty());
+        assert!(summarize(&SetList::default(), &items(), &pools).is_empty());
+    }
+
+    #[test]
+    fn bonuses_past_the_list_are_zero() {
+        let leaf = &sets().sets["Leaf"];
+
+        assert_eq!(leaf.bonus(0, "hp"), 0.0);
+        assert_eq!(leaf.bonus(3, "hp"), 50.0);
+        assert_eq!(leaf.bonus(4, "hp"), 0.0);
+        assert_eq!(leaf.bonus(3, "note"), 0.0);
+        assert_eq!(sets().sets["Ghost"].bonus(2, "hp"), 0.0);
+    }
+
+    #[test]
+    fn membership_falls_back_to_the_item() {
+        let sets = sets();
+        let loose = gear(GearType::Ring, "Loose Ring");
+        assert_eq!(sets.set_of(&loose), None);
+
+        let tagged = Gear { set: Some("Ring Set".to_string()), ..loose };
+        assert_eq!(sets.set_of(&tagged), Some("Ring Set"));
+        // The set definitions win over the item's own key
+        let leaf_cap = Gear { set: Some("Other".to_string()), ..items().items[0].clone() };
+        assert_eq!(sets.set_of(&leaf_cap), Some("Leaf"));
+    }
+}
@@ -0,0 +1,245 @@
+use super::{Gear, GearSlot};
+
+/// Strength, dexterity, intelligence, defense and agility, in the game's order.
+pub type Points = [i32; 5];
+
+pub const SKILLS: [&str; 5] = ["Strength", "Dexterity", "Intelligence", "Defense", "Agility"];
+/// items.json keys of the skill point bonuses, in `SKILLS` order.
+const BONUS_KEYS: [&str; 5] = ["str", "dex", "int", "def", "agi"];
+/// Most points a single skill can be given by hand.
+pub const MAX_ASSIGNED: i32 = 100;
+
+/// The eight armour and accessory slots of a build; rings take two.
+pub const EQUIP_SLOTS: [(GearSlot, &str); 8] = [
+    (GearSlot::Helmet, "Helmet"),
+    (GearSlot::Chestplate, "Chestplate"),
+    (GearSlot::Leggings, "Leggings"),
+    (GearSlot::Boots, "Boots"),
+    (GearSlot::Ring, "Ring 1"),
+    (GearSlot::Ring, "Ring 2"),
+    (GearSlot::Bracelet, "Bracelet"),
+    (GearSlot::Necklace, "Necklace"),
+];
+
+/// The fewest hand-assigned points that let every item of a build be worn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    pub assigned: Points,
+    /// Assigned points plus every item's bonus, once all items are on.
+    pub totals: Points,
+    /// Order to put the items on in; the weapon always goes last.
+    pub order: Vec<String>,
+}
+
+impl Allocation {
+    pub fn spent(&self) -> i32 {
+        cost(&self.assigned)
+    }
+}
+
+pub fn requirements(gear: &Gear) -> Points {
+    gear.requirements.iter().map(|(_, points)| points)
+}
+
+pub fn bonuses(gear: &Gear) -> Points {
+    BONUS_KEYS.map(|key| gear.stat(key) as i32)
+}
+
+/// Finds the cheapest allocation for `armour` plus an optional `weapon`.
+///
+/// An item can only be put on while the points so far (assigned plus the bonuses of
+/// items already worn) meet its requirements, and every requirement must still be met
+/// once everything is on, as negative bonuses can take points away again. Only the
+/// order of items with bonuses matters, so those are tried in every order and the
+/// rest go on after them. Orders that need more than `MAX_ASSIGNED` in any skill can't
+/// be used, however cheap they are overall.
+pub fn solve(armour: &[&Gear], weapon: Option<&Gear>) -> Result<Allocation, String> {
+    let (mut with_bonus, plain): (Vec<&Gear>, Vec<&Gear>) =
+        armour.iter().partition(|gear| bonuses(gear) != [0; 5]);
+
+    let mut best: Option<(Points, Vec<&Gear>)> = None;
+    // Without a usable order, the one whose largest skill is smallest explains why
+    let mut closest: Option<Points> = None;
+    for_each_order(&mut with_bonus, 0, &mut |order| {
+        let assigned = needed_points(order, &plain, weapon);
+        if highest(&assigned) > MAX_ASSIGNED {
+            if closest.is_none_or(|closest| highest(&assigned) < highest(&closest)) {
+                closest = Some(assigned);
+            }
+            return;
+        }
+        if best.as_ref().is_none_or(|(best_assigned, _)| cost(&assigned) < cost(best_assigned)) {
+            best = Some((assigned, order.to_vec()));
+        }
+    });
+
+    let Some((assigned, order)) = best else {
+        // There's always at least the empty order, so one of the two is set
+        let closest = closest.unwrap_or_default();
+        let skill = (0..5).max_by_key(|&skill| closest[skill]).unwrap_or_default();
+        return Err(format!(
+            "Needs {} {} points, but at most {} can be assigned to one skill",
+            closest[skill], SKILLS[skill], MAX_ASSIGNED
+        ));
+    };
+
+    let worn: Vec<&Gear> = order.into_iter().chain(plain).chain(weapon).collect();
+    let mut totals = assigned;
+    for gear in &worn {
+        add(&mut totals, &bonuses(gear));
+    }
+    Ok(Allocation {
+        assigned,
+        totals,
+        order: worn.iter().map(|gear| gear.name.clone()).collect(),
+    })
+}
+
+/// Points to assign so the items can be put on in the given order.
+fn needed_points(order: &[&Gear], plain: &[&Gear], weapon: Option<&Gear>) -> Points {
+    let mut needed = [0; 5];
+    let mut current = [0; 5];
+    for gear in order.iter().chain(plain).copied().chain(weapon) {
+        require(&mut needed, &current, gear);
+        add(&mut current, &bonuses(gear));
+    }
+    // Bonuses of later items can be negative, so check everything again at the end,
+    // still without each item's own bonus
+    for gear in order.iter().chain(plain).copied().chain(weapon) {
+        let own = bonuses(gear);
+        let others: Points = std::array::from_fn(|skill| current[skill] - own[skill]);
+        require(&mut needed, &others, gear);
+    }
+    needed
+}
+
+/// Raises `needed` so `gear`'s requirements are met on top of `current`.
+fn require(needed: &mut Points, current: &Points, gear: &Gear) {
+    for (skill, required) in requirements(gear).into_iter().enumerate() {
+        if required > 0 {
+            needed[skill] = needed[skill].max(required - current[skill]);
+        }
+    }
+}
+
+fn cost(assigned: &Points) -> i32 {
+    assigned.iter().sum()
+}
+
+fn highest(assigned: &Points) -> i32 {
+    assigned.iter().copied().max().unwrap_or_default()
+}
+
+fn add(points: &mut Points, other: &Points) {
+    for (point, other) in points.iter_mut().zip(other) {
+        *point += other;
+    }
+}
+
+/// Calls `f` with every ordering of `items[start..]`, at most 8! for a full build.
+fn for_each_order<'a>(items: &mut Vec<&'a Gear>, start: usize, f: &mut impl FnMut(&[&'a Gear])) {
+    if start >= items.len() {
+        f(items);
+        return;
+    }
+    for idx in start..items.len() {
+        items.swap(start, idx);
+        for_each_order(items, start + 1, f);
+        items.swap(start, idx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::gear;
+    use crate::config::GearType;
+
+    fn ring(name: &str, requirements: Points, bonuses: Points) -> Gear {
+        let mut ring = gear(GearType::Ring, name);
+        [
+            ring.requirements.strength,
+            ring.requirements.dexterity,
+            ring.requirements.intelligence,
+            ring.requirements.defense,
+            ring.requirements.agility,
+        ] = requirements;
+        for (key, bonus) in BONUS_KEYS.into_iter().zip(bonuses) {
+            if bonus != 0 {
+                ring.stats.insert(key.to_string(), bonus.into());
+            }
+        }
+        ring
+    }
+
+    #[test]
+    fn bonus_items_go_on_first() {
+        let booster = ring("Booster", [0; 5], [20, 0, 0, 0, 0]);
+        let heavy = ring("Heavy", [50, 0, 0, 0, 0], [0; 5]);
+
+        let allocation = solve(&[&heavy, &booster], None).unwrap();
+
+        assert_eq!(allocation.assigned, [30, 0, 0, 0, 0]);
+        assert_eq!(allocation.totals, [50, 0, 0, 0, 0]);
+        assert_eq!(allocation.order, vec!["Booster", "Heavy"]);
+    }
+
+    #[test]
+    fn an_items_own_bonus_does_not_count() {
+        let selfish = ring("Selfish", [40, 0, 0, 0, 0], [10, 0, 0, 0, 0]);
+
+        assert_eq!(solve(&[&selfish], None).unwrap().assigned, [40, 0, 0, 0, 0]);
+
+        // Not even when it's negative, but another item's negative bonus does count
+        let draining = ring("Draining", [40, 0, 0, 0, 0], [-10, 0, 0, 0, 0]);
+        assert_eq!(solve(&[&draining], None).unwrap().assigned, [40, 0, 0, 0, 0]);
+        let drain = ring("Drain", [0; 5], [-5, 0, 0, 0, 0]);
+        assert_eq!(solve(&[&draining, &drain], None).unwrap().assigned, [45, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn order_between_bonus_items_is_searched() {
+        // A needs B's dexterity and B needs A's strength: one of them has to be paid for
+        let a = ring("A", [0, 30, 0, 0, 0], [30, 0, 0, 0, 0]);
+        let b = ring("B", [20, 0, 0, 0, 0], [0, 30, 0, 0, 0]);
+
+        let allocation = solve(&[&a, &b], None).unwrap();
+
+        assert_eq!(allocation.spent(), 20);
+        assert_eq!(allocation.order, vec!["B", "A"]);
+    }
+
+    #[test]
+    fn the_cap_rules_out_cheaper_orders() {
+        // Heavy first costs 105 + 20 = 125 but breaks the cap; Booster first costs 60 + 95
+        let heavy = ring("Heavy", [0, 105, 0, 0, 0], [40, 0, 0, 0, 0]);
+        let booster = ring("Booster", [60, 0, 0, 0, 0], [0, 10, 0, 0, 0]);
+
+        let allocation = solve(&[&heavy, &booster], None).unwrap();
+
+        assert_eq!(allocation.assigned, [60, 95, 0, 0, 0]);
+        assert_eq!(allocation.order, vec!["Booster", "Heavy"]);
+    }
+
+    #[test]
+    fn negative_bonuses_are_checked_at_the_end() {
+        let needy = ring("Needy", [0, 0, 30, 0, 0], [0; 5]);
+        let drain = ring("Drain", [0; 5], [0, 0, -10, 0, 0]);
+
+        assert_eq!(solve(&[&needy, &drain], None).unwrap().assigned, [0, 0, 40, 0, 0]);
+    }
+
+    #[test]
+    fn weapon_goes_last_and_limits_apply() {
+        let booster = ring("Booster", [0; 5], [0, 0, 0, 0, 15]);
+        let mut weapon = ring("Bow", [0, 0, 0, 0, 110], [0; 5]);
+        weapon.gear_type = GearType::Bow;
+
+        let allocation = solve(&[&booster], Some(&weapon)).unwrap();
+        assert_eq!(allocation.assigned, [0, 0, 0, 0, 95]);
+        assert_eq!(allocation.order, vec!["Booster", "Bow"]);
+
+        weapon.requirements.agility = 130;
+        assert!(solve(&[&booster], Some(&weapon)).is_err());
+    }
+}
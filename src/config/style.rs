@@ -12,3 +12,22 @@ pub const LABEL_WIDTH: f32 = 150.0;
 // Color palette
 pub const WARNING: Color = Color::from_rgb(0.8, 0.4, 0.0);
 pub const ERROR: Color = Color::from_rgb(0.8, 0.0, 0.0);
+
+/// Name colour of an item tier, as in game.
+pub fn tier_color(tier: &str) -> Color {
+    tier_accent(tier).unwrap_or(Color::WHITE)
+}
+
+/// Like `tier_color`, but `None` for Normal and unknown tiers so themed widgets
+/// keep their own text colour instead of white.
+pub fn tier_accent(tier: &str) -> Option<Color> {
+    match tier {
+        "Unique" => Some(Color::from_rgb(1.0, 1.0, 0.33)),
+        "Rare" => Some(Color::from_rgb(1.0, 0.33, 1.0)),
+        "Legendary" => Some(Color::from_rgb(0.33, 1.0, 1.0)),
+        "Fabled" => Some(Color::from_rgb(1.0, 0.33, 0.33)),
+        "Mythic" => Some(Color::from_rgb(0.67, 0.0, 0.67)),
+        "Set" => Some(Color::from_rgb(0.33, 1.0, 0.33)),
+        _ => None,
+    }
+}
@@ -0,0 +1,307 @@
+use std::ops::RangeInclusive;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::build_config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThresholdSection {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+}
+
+impl ThresholdSection {
+    pub const ALL: [ThresholdSection; 5] = [
+        ThresholdSection::First,
+        ThresholdSection::Second,
+        ThresholdSection::Third,
+        ThresholdSection::Fourth,
+        ThresholdSection::Fifth,
+    ];
+
+    /// Name of the section's table in `config.toml`.
+    pub fn key(self) -> &'static str {
+        match self {
+            ThresholdSection::First => "threshold_first",
+            ThresholdSection::Second => "threshold_second",
+            ThresholdSection::Third => "threshold_third",
+            ThresholdSection::Fourth => "threshold_fourth",
+            ThresholdSection::Fifth => "threshold_fifth",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            ThresholdSection::First => "Threshold First Settings",
+            ThresholdSection::Second => "Threshold Second Settings",
+            ThresholdSection::Third => "Threshold Third Settings",
+            ThresholdSection::Fourth => "Threshold Fourth Settings",
+            ThresholdSection::Fifth => "Threshold Fifth Settings",
+        }
+    }
+
+    pub fn fields(self) -> impl Iterator<Item = &'static ThresholdField> {
+        THRESHOLD_FIELDS
+            .iter()
+            .filter(move |field| field.section == self)
+    }
+
+    pub fn is_set(self, config: &Config) -> bool {
+        self.table(config).is_some()
+    }
+
+    /// Removes the section from the config and returns what it held, so switching the
+    /// section back on can restore it.
+    pub fn take(self, config: &mut Config) -> Option<toml::Table> {
+        let table = self.table(config);
+        // Setting a section to nothing cannot fail to deserialize
+        let _ = self.set_table(config, None);
+        table
+    }
+
+    /// The section as a TOML table, or `None` when it is absent from the config.
+    pub fn table(self, config: &Config) -> Option<toml::Table> {
+        match self {
+            ThresholdSection::First => to_table(&config.threshold_first),
+            ThresholdSection::Second => to_table(&config.threshold_second),
+            ThresholdSection::Third => to_table(&config.threshold_third),
+            ThresholdSection::Fourth => to_table(&config.threshold_fourth),
+            ThresholdSection::Fifth => to_table(&config.threshold_fifth),
+        }
+    }
+
+    /// Replaces the section; an empty table removes it so the TOML stays minimal.
+    pub fn set_table(self, config: &mut Config, table: Option<toml::Table>) -> Result<(), String> {
+        let table = table.filter(|table| !table.is_empty());
+        match self {
+            ThresholdSection::First => config.threshold_first = from_table(table)?,
+            ThresholdSection::Second => config.threshold_second = from_table(table)?,
+            ThresholdSection::Third => config.threshold_third = from_table(table)?,
+            ThresholdSection::Fourth => config.threshold_fourth = from_table(table)?,
+            ThresholdSection::Fifth => config.threshold_fifth = from_table(table)?,
+        }
+        Ok(())
+    }
+}
+
+fn to_table<T: Serialize>(section: &Option<T>) -> Option<toml::Table> {
+    section
+        .as_ref()
+        .and_then(|section| toml::Table::try_from(section).ok())
+}
+
+fn from_table<T: DeserializeOwned>(table: Option<toml::Table>) -> Result<Option<T>, String> {
+    table
+        .map(|table| table.try_into().map_err(|e: toml::de::Error| e.to_string()))
+        .transpose()
+}
+
+/// Integer width of the field as the builder reads it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericType {
+    I16,
+    I32,
+}
+
+impl NumericType {
+    pub fn fits(self, value: i64) -> bool {
+        match self {
+            NumericType::I16 => i16::try_from(value).is_ok(),
+            NumericType::I32 => i32::try_from(value).is_ok(),
+        }
+    }
+}
+
+/// Describes one threshold input. The config view renders a row per entry and
+/// updates write the value under `key` in the section's table, so exposing a
+/// new builder threshold only takes a new entry in `THRESHOLD_FIELDS`.
+#[derive(Debug)]
+pub struct ThresholdField {
+    pub section: ThresholdSection,
+    pub key: &'static str,
+    pub label: &'static str,
+    pub numeric: NumericType,
+    pub range: RangeInclusive<i64>,
+    pub unit: &'static str,
+}
+
+impl ThresholdField {
+    /// `threshold_<section>.<key>`, the key used for validation errors.
+    pub fn path(&self) -> String {
+        format!("{}.{}", self.section.key(), self.key)
+    }
+
+    pub fn value(&self, config: &Config) -> Option<i64> {
+        self.section
+            .table(config)?
+            .get(self.key)
+            .and_then(toml::Value::as_integer)
+    }
+
+    pub fn set(&self, config: &mut Config, value: Option<i64>) -> Result<(), String> {
+        let mut table = self.section.table(config).unwrap_or_default();
+        match value {
+            Some(value) => table.insert(self.key.to_string(), toml::Value::Integer(value)),
+            None => table.remove(self.key),
+        };
+        self.section.set_table(config, Some(table))
+    }
+}
+
+pub fn field(section: ThresholdSection, key: &str) -> Option<&'static ThresholdField> {
+    section.fields().find(|field| field.key == key)
+}
+
+macro_rules! threshold_fields {
+    ($($section:ident, $key:literal, $label:literal, $numeric:ident, $min:literal..=$max:literal, $unit:literal;)*) => {
+        pub static THRESHOLD_FIELDS: &[ThresholdField] = &[
+            $(ThresholdField {
+                section: ThresholdSection::$section,
+                key: $key,
+                label: $label,
+                numeric: NumericType::$numeric,
+                range: $min..=$max,
+                unit: $unit,
+            }),*
+        ];
+    };
+}
+
+threshold_fields! {
+    First, "min_hp", "Min HP", I32, 0..=50_000, "HP";
+
+    Second, "min_hpr_raw", "Min Health Regen Raw", I16, -2_000..=5_000, "";
+    Second, "min_hpr_pct", "Min Health Regen", I16, -300..=500, "%";
+    Second, "min_mr", "Min Mana Regen", I16, -100..=100, "/5s";
+    Second, "min_ls", "Min Life Steal", I16, -2_000..=3_000, "/3s";
+    Second, "min_ms", "Min Mana Steal", I16, -100..=100, "/3s";
+    Second, "min_spd", "Min Speed", I16, -300..=500, "%";
+    Second, "min_sd_raw", "Min Spell Damage Raw", I16, -5_000..=10_000, "";
+    Second, "min_sd_pct", "Min Spell Damage", I16, -300..=1_000, "%";
+    Second, "min_hpr", "Min Total Health Regen", I32, -10_000..=20_000, "";
+    Second, "min_exp_bonus", "Min Experience Bonus", I32, -100..=1_000, "%";
+
+    Third, "min_earth_defense", "Min Earth Defense", I16, -5_000..=10_000, "";
+    Third, "min_thunder_defense", "Min Thunder Defense", I16, -5_000..=10_000, "";
+    Third, "min_water_defense", "Min Water Defense", I16, -5_000..=10_000, "";
+    Third, "min_fire_defense", "Min Fire Defense", I16, -5_000..=10_000, "";
+    Third, "min_air_defense", "Min Air Defense", I16, -5_000..=10_000, "";
+
+    Fourth, "min_neutral_dam_pct", "Min Neutral Damage", I16, -300..=1_000, "%";
+    Fourth, "min_earth_dam_pct", "Min Earth Damage", I16, -300..=1_000, "%";
+    Fourth, "min_thunder_dam_pct", "Min Thunder Damage", I16, -300..=1_000, "%";
+    Fourth, "min_water_dam_pct", "Min Water Damage", I16, -300..=1_000, "%";
+    Fourth, "min_fire_dam_pct", "Min Fire Damage", I16, -300..=1_000, "%";
+    Fourth, "min_air_dam_pct", "Min Air Damage", I16, -300..=1_000, "%";
+
+    Fifth, "min_earth_point", "Min Earth Point", I16, 0..=150, "pts";
+    Fifth, "min_thunder_point", "Min Thunder Point", I16, 0..=150, "pts";
+    Fifth, "min_water_point", "Min Water Point", I16, 0..=150, "pts";
+    Fifth, "min_fire_point", "Min Fire Point", I16, 0..=150, "pts";
+    Fifth, "min_air_point", "Min Air Point", I16, 0..=150, "pts";
+    Fifth, "min_ehp", "Min EHP", I32, 0..=1_000_000, "EHP";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extra(section: ThresholdSection, config: &Config) -> toml::Table {
+        let mut table = section.table(config).unwrap_or_default();
+        table.retain(|key, _| section.fields().all(|field| field.key != key));
+        table
+    }
+
+    #[test]
+    fn every_descriptor_targets_a_typed_field() {
+        for field in THRESHOLD_FIELDS {
+            let mut config = Config::default();
+
+            field.set(&mut config, Some(*field.range.end())).unwrap();
+
+            assert_eq!(field.value(&config), Some(*field.range.end()), "{}", field.path());
+            assert!(extra(field.section, &config).is_empty(), "{}", field.path());
+        }
+    }
+
+    #[test]
+    fn descriptor_ranges_fit_their_numeric_type() {
+        for field in THRESHOLD_FIELDS {
+            assert!(field.numeric.fits(*field.range.start()), "{}", field.path());
+            assert!(field.numeric.fits(*field.range.end()), "{}", field.path());
+        }
+    }
+
+    #[test]
+    fn clearing_the_last_field_removes_the_section() {
+        let mut config = Config::default();
+        let hp = field(ThresholdSection::First, "min_hp").unwrap();
+
+        hp.set(&mut config, Some(10_000)).unwrap();
+        assert_eq!(config.threshold_first.as_ref().and_then(|first| first.min_hp), Some(10_000));
+
+        hp.set(&mut config, None).unwrap();
+        assert!(config.threshold_first.is_none());
+    }
+
+    #[test]
+    fn taken_sections_can_be_restored() {
+        let mut config = Config::default();
+        field(ThresholdSection::Second, "min_mr").unwrap().set(&mut config, Some(50)).unwrap();
+
+        let taken = ThresholdSection::Second.take(&mut config);
+        assert!(!ThresholdSection::Second.is_set(&config));
+        assert_eq!(ThresholdSection::Second.take(&mut config), None);
+
+        ThresholdSection::Second.set_table(&mut config, taken).unwrap();
+        assert_eq!(field(ThresholdSection::Second, "min_mr").unwrap().value(&config), Some(50));
+    }
+
+    #[test]
+    fn unknown_keys_survive_edits() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [items]
+            helmets = []
+            chest_plates = []
+            leggings = []
+            boots = []
+            rings = []
+            bracelets = []
+            necklaces = []
+            weapon = ""
+
+            [player]
+            lvl = 106
+            available_point = 200
+            base_hp = 500
+
+            [hppeng]
+            url_prefix = ""
+            url_suffix = ""
+            log_builds = false
+            db_path = ""
+            migrations_path = ""
+            items_file = ""
+            log_db_errors = false
+            db_retry_count = 3
+
+            [threshold_second]
+            min_future_stat = 7
+            "#,
+        )
+        .unwrap();
+
+        field(ThresholdSection::Second, "min_mr")
+            .unwrap()
+            .set(&mut config, Some(5))
+            .unwrap();
+
+        let table = ThresholdSection::Second.table(&config).unwrap();
+        assert_eq!(table.get("min_future_stat"), Some(&toml::Value::Integer(7)));
+        assert_eq!(table.get("min_mr"), Some(&toml::Value::Integer(5)));
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+
+use crate::build_config::Config;
+
+use super::thresholds::THRESHOLD_FIELDS;
+use super::{GearList, GearSlot};
+
+// Game limits
+pub const MIN_LEVEL: i32 = 1;
+pub const MAX_LEVEL: i32 = 106;
+pub const MAX_AVAILABLE_POINTS: i16 = 200;
+
+/// Validation errors keyed by the TOML path of the offending field,
+/// e.g. `player.lvl` or `items.helmets.2`. Warnings use the same keys but
+/// don't stop the builder from running.
+#[derive(Debug, Default, Clone)]
+pub struct ConfigErrors {
+    errors: BTreeMap<String, String>,
+    warnings: BTreeMap<String, String>,
+}
+
+impl ConfigErrors {
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.errors.get(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.errors.iter()
+    }
+
+    pub fn warning(&self, key: &str) -> Option<&String> {
+        self.warnings.get(key)
+    }
+
+    fn push(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.errors.insert(key.into(), message.into());
+    }
+
+    fn warn(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.warnings.insert(key.into(), message.into());
+    }
+}
+
+/// Without a `gear_list` (items.json still loading) pooled items aren't looked up.
+/// `drafts` holds typed text that didn't parse, keyed by field path.
+pub fn validate(config: &Config, gear_list: Option<&GearList>, drafts: &BTreeMap<String, String>) -> ConfigErrors {
+    let mut errors = ConfigErrors::default();
+
+    // === Player ===
+    let player = &config.player;
+    if !(MIN_LEVEL..=MAX_LEVEL).contains(&player.lvl) {
+        errors.push(
+            "player.lvl",
+            format!("Level must be between {} and {}", MIN_LEVEL, MAX_LEVEL),
+        );
+    }
+
+    // Two points per level up, capped at 200
+    let max_points = ((player.lvl.clamp(MIN_LEVEL, MAX_LEVEL) - 1) * 2)
+        .min(MAX_AVAILABLE_POINTS as i32) as i16;
+    if !(0..=max_points).contains(&player.available_point) {
+        errors.push(
+            "player.available_point",
+            format!("Must be between 0 and {} at level {}", max_points, player.lvl),
+        );
+    }
+
+    if player.base_hp <= 0 {
+        errors.push("player.base_hp", "Base HP must be positive");
+    }
+
+    // === Items ===
+    let items = &config.items;
+    for slot in GearSlot::ALL {
+        let pool = slot.pool(items);
+        if pool.is_empty() {
+            errors.push(format!("items.{}", slot.key()), "Add at least one item");
+        }
+        let Some(gear_list) = gear_list else {
+            continue;
+        };
+
+        for (idx, name) in pool.iter().enumerate() {
+            match gear_list.find(name) {
+                None => errors.push(
+                    format!("items.{}.{}", slot.key(), idx),
+                    format!("\"{}\" was not found in items.json", name),
+                ),
+                Some(gear) if gear.gear_type != slot.gear_type() => errors.push(
+                    format!("items.{}.{}", slot.key(), idx),
+                    format!("\"{}\" is a {:?}, not a {:?}", name, gear.gear_type, slot.gear_type()),
+                ),
+                Some(gear) => {
+                    if let (Some(class), Some(required)) = (player.class, gear.class_req) {
+                        if class != required {
+                            errors.warn(
+                                format!("items.{}.{}", slot.key(), idx),
+                                format!("\"{}\" can only be used by a {}", name, required),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if items.weapon.is_empty() {
+        errors.push("items.weapon", "Select a weapon");
+    } else if let Some(gear_list) = gear_list {
+        match gear_list.find(&items.weapon) {
+            None => errors.push(
+                "items.weapon",
+                format!("\"{}\" was not found in items.json", items.weapon),
+            ),
+            Some(gear) if !gear.gear_type.is_weapon() => errors.push(
+                "items.weapon",
+                format!("\"{}\" is a {:?}, not a weapon", items.weapon, gear.gear_type),
+            ),
+            Some(gear) => {
+                if let Some(class) = player.class.filter(|class| !class.can_use(gear)) {
+                    errors.warn(
+                        "items.weapon",
+                        format!(
+                            "A {} can't use \"{}\", their weapon is a {:?}",
+                            class,
+                            items.weapon,
+                            class.weapon_type()
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    // === Illegal combinations ===
+    for (group_idx, group) in items.illegal_combinations.iter().flatten().enumerate() {
+        let key = format!("items.illegal_combinations.{}", group_idx);
+        let unpooled: Vec<&str> = group
+            .iter()
+            .filter(|name| !items.pooled().any(|pooled| pooled == *name))
+            .map(String::as_str)
+            .collect();
+
+        if !unpooled.is_empty() {
+            errors.push(key, format!("Not in any gear pool: {}", unpooled.join(", ")));
+        } else if group.len() < 2 {
+            errors.push(key, "A combination needs at least two items");
+        }
+    }
+
+    // === Thresholds ===
+    for field in THRESHOLD_FIELDS {
+        if let Some(value) = field.value(config) {
+            if !field.range.contains(&value) {
+                errors.push(
+                    field.path(),
+                    format!("Must be between {} and {}", field.range.start(), field.range.end()),
+                );
+            }
+        }
+    }
+
+    // EHP is never lower than raw HP, so a lower EHP floor contradicts the HP floor
+    let min_hp = config.threshold_first.as_ref().and_then(|first| first.min_hp);
+    let min_ehp = config.threshold_fifth.as_ref().and_then(|fifth| fifth.min_ehp);
+    if let (Some(min_hp), Some(min_ehp)) = (min_hp, min_ehp) {
+        if min_ehp < min_hp {
+            errors.push(
+                "threshold_fifth.min_ehp",
+                format!("Min EHP is lower than Min HP ({})", min_hp),
+            );
+        }
+    }
+
+    // Typed text that didn't parse replaces the field's value, so it's the error to show
+    for (key, draft) in drafts {
+        if draft.trim().is_empty() {
+            errors.push(key.as_str(), "Enter a value");
+        } else {
+            errors.push(key.as_str(), format!("\"{}\" is not a number this field can hold", draft));
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list};
+    use crate::config::{Class, Gear, GearType};
+
+    /// A config that passes: one item per pool, a weapon and a level 106 player.
+    fn valid_config() -> Config {
+        let mut config = Config::default();
+        config.player.lvl = 106;
+        config.player.available_point = 200;
+        config.player.base_hp = 500;
+        for slot in GearSlot::ALL {
+            slot.pool_mut(&mut config.items).push(format!("{:?}", slot.gear_type()));
+        }
+        config.items.weapon = "Bow".to_string();
+        config
+    }
+
+    fn items() -> GearList {
+        let pooled = GearSlot::ALL.map(|slot| gear(slot.gear_type(), &format!("{:?}", slot.gear_type())));
+        let bow = Gear {
+            class_req: Some(Class::Archer),
+            ..gear(GearType::Bow, "Bow")
+        };
+        gear_list(pooled.into_iter().chain([bow]))
+    }
+
+    fn check(config: &Config) -> ConfigErrors {
+        validate(config, Some(&items()), &BTreeMap::new())
+    }
+
+    #[test]
+    fn valid_config_has_no_errors() {
+        let errors = check(&valid_config());
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn level_bounds() {
+        let mut config = valid_config();
+        config.player.available_point = 0;
+        for (lvl, valid) in [(0, false), (1, true), (106, true), (107, false)] {
+            config.player.lvl = lvl;
+            assert_eq!(check(&config).get("player.lvl").is_none(), valid, "level {}", lvl);
+        }
+    }
+
+    #[test]
+    fn points_are_capped_by_level_and_200() {
+        let mut config = valid_config();
+        config.player.lvl = 50;
+        config.player.available_point = 98;
+        assert!(check(&config).is_empty());
+        config.player.available_point = 99;
+        assert!(check(&config).get("player.available_point").is_some());
+
+        config.player.lvl = 106;
+        config.player.available_point = 201;
+        assert!(check(&config).get("player.available_point").is_some());
+        config.player.available_point = -1;
+        assert!(check(&config).get("player.available_point").is_some());
+    }
+
+    #[test]
+    fn unknown_and_misplaced_pool_items() {
+        let mut config = valid_config();
+        config.items.helmets.push("Nothing Like It".to_string());
+        config.items.rings.push("Helmet".to_string());
+
+        let errors = check(&config);
+
+        assert!(errors.get("items.helmets.1").unwrap().contains("not found"));
+        assert!(errors.get("items.rings.1").unwrap().contains("not a Ring"));
+        // Only looked up once items.json is loaded
+        assert!(validate(&config, None, &BTreeMap::new()).is_empty());
+    }
+
+    #[test]
+    fn missing_non_weapon_and_wrong_class_weapons() {
+        let mut config = valid_config();
+        config.items.weapon.clear();
+        assert_eq!(check(&config).get("items.weapon").map(String::as_str), Some("Select a weapon"));
+
+        config.items.weapon = "Helmet".to_string();
+        assert!(check(&config).get("items.weapon").unwrap().contains("not a weapon"));
+
+        config.items.weapon = "Bow".to_string();
+        config.player.class = Some(Class::Mage);
+        let errors = check(&config);
+        assert!(errors.is_empty());
+        assert!(errors.warning("items.weapon").is_some());
+    }
+
+    #[test]
+    fn unparsed_drafts_are_errors() {
+        let drafts = BTreeMap::from([
+            ("player.lvl".to_string(), "".to_string()),
+            ("player.base_hp".to_string(), "99999999999".to_string()),
+        ]);
+
+        let errors = validate(&valid_config(), Some(&items()), &drafts);
+
+        assert_eq!(errors.get("player.lvl").map(String::as_str), Some("Enter a value"));
+        assert!(errors.get("player.base_hp").unwrap().contains("99999999999"));
+    }
+}
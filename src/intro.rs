@@ -45,7 +45,7 @@ impl Intro {
                 ],
                 text("Instructions for first time setup").size(20),
                 text("1. Make sure you've extracted both the release Wynn Builder UI and the WynnBuilderTools release into the same folder.").size(16),
-                text("2. Go to the Search tab, and run any command with --sort or -s to generate the items.json file.").size(16),
+                text("2. Go to the Search tab, and run any command with --sort or -s to generate the items.json file.\nAlternatively, set the API URL in the Config File tab and press Update Item Database.").size(16),
                 text("3. Once the items.json file is generated, go to the Config File tab, and check that you get no errors.\nIf you do, check that items.json is present in the config folder, and close and re-open the application.").size(16),
                 text("4. Once you've confirmed that the config tab loads without errors, you're set for configuration.").size(16),
                 text("5. You can now use the Builder tab to build the database of possible gear combinations with the given config.").size(16),
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::build_config::Items;
+use crate::config::{stat_label, Gear, GearList};
+
+/// What changed between two item databases.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Changelog {
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<ItemChange>,
+}
+
+/// An item present in both databases whose data differs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemChange {
+    pub name: String,
+    /// One line per changed stat, e.g. `Health: 3000 -> 3200`.
+    pub changes: Vec<String>,
+}
+
+impl Changelog {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Removed or changed items that are in a gear pool or the weapon slot; configs
+    /// using them give different results now and need re-running.
+    pub fn affected(&self, items: &Items) -> Vec<&str> {
+        let pooled: BTreeSet<&String> = items.pooled().collect();
+        self.removed
+            .iter()
+            .chain(self.changed.iter().map(|change| &change.name))
+            .filter(|name| pooled.contains(name))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Compares items by name, in the order of `new` (`old` for removed ones). A name listed
+/// more than once counts as its first entry.
+pub fn diff(old: &GearList, new: &GearList) -> Changelog {
+    let mut old_items: BTreeMap<&str, &Gear> = BTreeMap::new();
+    for gear in &old.items {
+        old_items.entry(gear.name.as_str()).or_insert(gear);
+    }
+    let mut new_names: BTreeSet<&str> = BTreeSet::new();
+
+    let mut changelog = Changelog::default();
+    for gear in new.items.iter().filter(|gear| new_names.insert(gear.name.as_str())) {
+        match old_items.get(gear.name.as_str()) {
+            None => changelog.added.push(gear.name.clone()),
+            Some(old_gear) => {
+                let changes = stat_changes(old_gear, gear);
+                if !changes.is_empty() {
+                    changelog.changed.push(ItemChange {
+                        name: gear.name.clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    changelog.removed = old
+        .items
+        .iter()
+        .filter(|gear| new_names.insert(gear.name.as_str()))
+        .map(|gear| gear.name.clone())
+        .collect();
+    changelog
+}
+
+/// Compares the items as items.json writes them, so every field counts. Absent and
+/// `null` are the same, and the id is ignored as it can be reassigned.
+fn stat_changes(old: &Gear, new: &Gear) -> Vec<String> {
+    let fields = |gear: &Gear| -> BTreeMap<String, serde_json::Value> {
+        match serde_json::to_value(gear) {
+            Ok(serde_json::Value::Object(map)) => map
+                .into_iter()
+                .filter(|(key, value)| key != "id" && !value.is_null())
+                .collect(),
+            _ => BTreeMap::new(),
+        }
+    };
+    let (old, new) = (fields(old), fields(new));
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .map(|key| {
+            format!(
+                "{}: {} -> {}",
+                stat_label(key),
+                old.get(key).map(show).unwrap_or_else(|| "none".to_string()),
+                new.get(key).map(show).unwrap_or_else(|| "none".to_string()),
+            )
+        })
+        .collect()
+}
+
+fn show(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list, names, with_stats};
+    use crate::config::GearType;
+
+    fn ring(name: &str, hp: i32) -> Gear {
+        Gear { hp, ..gear(GearType::Ring, name) }
+    }
+
+    #[test]
+    fn added_removed_and_changed_items_are_listed() {
+        let old = gear_list([ring("Kept", 10), ring("Gone", 10), ring("Buffed", 10)]);
+        let new = gear_list([ring("Kept", 10), ring("Buffed", 20), ring("New", 10)]);
+
+        let changelog = diff(&old, &new);
+
+        assert_eq!(changelog.added, names(&["New"]));
+        assert_eq!(changelog.removed, names(&["Gone"]));
+        assert_eq!(
+            changelog.changed,
+            vec![ItemChange {
+                name: "Buffed".to_string(),
+                changes: names(&["Base Health: 10 -> 20"]),
+            }]
+        );
+    }
+
+    #[test]
+    fn empty_databases() {
+        let items = gear_list([ring("Yang", 10)]);
+
+        assert!(diff(&GearList::default(), &GearList::default()).is_empty());
+        assert_eq!(diff(&GearList::default(), &items).added, names(&["Yang"]));
+        assert_eq!(diff(&items, &GearList::default()).removed, names(&["Yang"]));
+    }
+
+    #[test]
+    fn repeated_names_are_listed_once() {
+        let old = gear_list([ring("Gone", 10), ring("Gone", 20), ring("Kept", 10), ring("Kept", 30)]);
+        let new = gear_list([ring("Kept", 10), ring("Kept", 40), ring("New", 10), ring("New", 10)]);
+
+        let changelog = diff(&old, &new);
+
+        assert_eq!(changelog.added, names(&["New"]));
+        assert_eq!(changelog.removed, names(&["Gone"]));
+        assert!(changelog.changed.is_empty());
+    }
+
+    #[test]
+    fn ids_and_nulls_are_not_changes() {
+        let old = gear_list([with_stats(ring("Yang", 10), &[("sdPct", 5)])]);
+        let mut moved = Gear { id: 99, ..old.items[0].clone() };
+        moved.stats.insert("set".to_string(), serde_json::Value::Null);
+
+        assert!(diff(&old, &gear_list([moved])).is_empty());
+    }
+
+    #[test]
+    fn identifications_use_their_labels() {
+        let old = with_stats(ring("Ring", 0), &[("spRaw1", -5)]);
+        let new = with_stats(ring("Ring", 0), &[("sdPct", 5)]);
+
+        assert_eq!(
+            stat_changes(&old, &new),
+            names(&["Spell Damage: none -> 5", "1st Spell Cost: -5 -> none"])
+        );
+
+        // Base health and the health identification read differently
+        let bonus = with_stats(ring("Ring", 10), &[("hpBonus", 100)]);
+        assert_eq!(
+            stat_changes(&ring("Ring", 0), &bonus),
+            names(&["Base Health: 0 -> 10", "Health: none -> 100"])
+        );
+    }
+
+    #[test]
+    fn only_pooled_items_are_affected() {
+        let changelog = Changelog {
+            added: names(&["New"]),
+            removed: names(&["Gone", "Unused"]),
+            changed: vec![ItemChange {
+                name: "Buffed".to_string(),
+                changes: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        let items = Items {
+            rings: names(&["Buffed", "New"]),
+            weapon: "Gone".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(changelog.affected(&items), vec!["Gone", "Buffed"]);
+        assert!(changelog.affected(&Items::default()).is_empty());
+
+        // An unset weapon is not an item named ""
+        let unnamed = Changelog {
+            removed: names(&[""]),
+            ..Default::default()
+        };
+        assert!(unnamed.affected(&Items::default()).is_empty());
+    }
+}
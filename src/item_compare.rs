@@ -0,0 +1,394 @@
+use iced::{
+    alignment::{Horizontal, Vertical},
+    Color, Element, Length, Task,
+};
+use iced_widget::{button, column, combo_box, container, row, scrollable, text, Container, Row};
+
+use crate::config::style::{tier_accent, BTN_PAD, HEADER, SPACE, SUBHEAD, TEXT};
+use crate::config::{Defenses, Gear, GearList, GearOption, GearType, SkillPoints, IDENTIFICATIONS};
+use crate::{ItemCompareMessage, Message};
+
+pub const MAX_ITEMS: usize = 4;
+const LABEL_WIDTH: f32 = 200.0;
+const BEST: Color = Color::from_rgb(0.2, 0.75, 0.2);
+const WORSE: Color = Color::from_rgb(0.8, 0.0, 0.0);
+/// items.json attack speeds, slowest first.
+const ATTACK_SPEEDS: [&str; 7] = ["SUPER_SLOW", "VERY_SLOW", "SLOW", "NORMAL", "FAST", "VERY_FAST", "SUPER_FAST"];
+/// Damage elements as `Damage::iter` labels them.
+const ELEMENTS: [&str; 6] = ["Neutral", "Earth", "Thunder", "Water", "Fire", "Air"];
+
+/// Up to four items of one gear type shown side by side.
+#[derive(Default)]
+pub struct ItemCompare {
+    pub items: Vec<String>,
+    /// Item deltas are measured against; the first shown item when unset or gone.
+    baseline: Option<String>,
+    gear_type: Option<GearType>,
+    /// Items of `gear_type` that can be added.
+    picker: combo_box::State<GearOption>,
+}
+
+/// One stat across the compared items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatRow {
+    pub label: String,
+    pub unit: &'static str,
+    pub values: Vec<i32>,
+    pub higher_is_better: bool,
+    /// Shown instead of the values when those only stand in for them, like the average
+    /// of a damage range or the tier of an attack speed.
+    pub texts: Option<Vec<String>>,
+}
+
+impl StatRow {
+    /// The best value, or `None` when every item has the same.
+    pub fn best(&self) -> Option<i32> {
+        let best = if self.higher_is_better {
+            self.values.iter().max()
+        } else {
+            self.values.iter().min()
+        }?;
+        self.values.iter().any(|value| value != best).then_some(*best)
+    }
+}
+
+impl ItemCompare {
+    pub fn update(&mut self, message: ItemCompareMessage, gear_list: &GearList) -> Task<Message> {
+        match message {
+            ItemCompareMessage::Add(name) => {
+                let Some(gear) = gear_list.find(&name) else {
+                    return Task::none();
+                };
+                // Only items of one type are comparable; starting another type starts over
+                if self.gear_type.as_ref() != Some(&gear.gear_type) {
+                    self.items.clear();
+                    self.baseline = None;
+                    self.gear_type = Some(gear.gear_type.clone());
+                    self.picker = combo_box::State::new(gear_list.get_gear_by_type(gear.gear_type.clone()));
+                }
+                // Items gone from items.json are dropped first, then the newest non-baseline one
+                self.items.retain(|item| gear_list.find(item).is_some());
+                if !self.items.contains(&name) {
+                    if self.items.len() == MAX_ITEMS {
+                        let baseline = self.baseline_name();
+                        if let Some(idx) = self.items.iter().rposition(|item| Some(item) != baseline.as_ref()) {
+                            self.items.remove(idx);
+                        }
+                    }
+                    self.items.push(name);
+                }
+            }
+            ItemCompareMessage::Remove(name) => {
+                self.items.retain(|item| *item != name);
+                if self.baseline.as_ref() == Some(&name) {
+                    self.baseline = None;
+                }
+            }
+            ItemCompareMessage::SetBaseline(name) => {
+                if self.items.contains(&name) {
+                    self.baseline = Some(name);
+                }
+            }
+            ItemCompareMessage::Clear => {
+                *self = Self::default();
+            }
+        }
+        Task::none()
+    }
+
+    /// The chosen baseline if it's still compared, else the first item.
+    fn baseline_name(&self) -> Option<String> {
+        self.baseline
+            .clone()
+            .filter(|baseline| self.items.contains(baseline))
+            .or_else(|| self.items.first().cloned())
+    }
+
+    pub fn view<'a>(&'a self, gear_list: &'a GearList) -> Container<'a, Message> {
+        let gears: Vec<&Gear> = self
+            .items
+            .iter()
+            .filter_map(|name| gear_list.find(name))
+            .collect();
+
+        let picker = combo_box(&self.picker, "Add item to compare...", None, |option: GearOption| {
+            Message::ItemCompare(ItemCompareMessage::Add(option.name))
+        })
+        .width(Length::Fixed(300.0));
+
+        let mut content = column![
+            text("Compare Items").size(HEADER),
+            text(format!(
+                "Compare up to {} items of the same type. Add them with the Compare buttons in the Config File and Search tabs, or below.",
+                MAX_ITEMS
+            ))
+            .size(SUBHEAD),
+            row![
+                picker,
+                button("Clear").on_press(Message::ItemCompare(ItemCompareMessage::Clear)).padding(BTN_PAD),
+            ]
+            .spacing(SPACE)
+            .align_y(Vertical::Center),
+        ]
+        .spacing(SPACE);
+
+        if gears.is_empty() {
+            content = content.push(text("No items selected.").size(TEXT));
+        } else {
+            // Positions in `gears`, which skips items no longer in items.json
+            let baseline = self
+                .baseline
+                .as_ref()
+                .and_then(|baseline| gears.iter().position(|gear| gear.name == *baseline))
+                .unwrap_or_default();
+            content = content.push(header(&gears, baseline));
+            for stat in stat_rows(&gears) {
+                content = content.push(stat_row(stat, baseline));
+            }
+        }
+
+        container(scrollable(content.padding(SPACE)).width(Length::Fill))
+            .align_x(Horizontal::Left)
+            .align_y(Vertical::Top)
+            .width(Length::Fill)
+            .height(Length::Fill)
+    }
+
+}
+
+fn header<'a>(gears: &[&'a Gear], baseline: usize) -> Row<'a, Message> {
+    gears.iter().enumerate().fold(
+        row![text("").width(Length::Fixed(LABEL_WIDTH))].spacing(SPACE),
+        |header, (idx, gear)| {
+            let baseline: Element<'a, Message> = if idx == baseline {
+                text("Baseline").size(TEXT).into()
+            } else {
+                button(text("Use as Baseline").size(TEXT))
+                    .on_press(Message::ItemCompare(ItemCompareMessage::SetBaseline(gear.name.clone())))
+                    .style(button::secondary)
+                    .padding(BTN_PAD)
+                    .into()
+            };
+            header.push(
+                column![
+                    text(&gear.name).size(SUBHEAD).color_maybe(tier_accent(&gear.tier)),
+                    row![
+                        baseline,
+                        button(text("Remove").size(TEXT))
+                            .on_press(Message::ItemCompare(ItemCompareMessage::Remove(gear.name.clone())))
+                            .style(button::secondary)
+                            .padding(BTN_PAD),
+                    ]
+                    .spacing(SPACE),
+                ]
+                .spacing(SPACE)
+                .width(Length::FillPortion(1)),
+            )
+        },
+    )
+}
+
+fn stat_row<'a>(stat: StatRow, baseline: usize) -> Row<'a, Message> {
+    let best = stat.best();
+    let baseline_value = stat.values.get(baseline).copied().unwrap_or_default();
+
+    stat.values.iter().enumerate().fold(
+        row![text(stat.label.clone()).size(TEXT).width(Length::Fixed(LABEL_WIDTH))].spacing(SPACE),
+        |row, (idx, value)| {
+            let delta = value - baseline_value;
+            let mut cell = match &stat.texts {
+                Some(texts) => texts[idx].clone(),
+                None => format!("{}{}", value, stat.unit),
+            };
+            if idx != baseline && delta != 0 {
+                cell.push_str(&format!(" ({:+})", delta));
+            }
+
+            let worse = delta != 0 && (delta > 0) != stat.higher_is_better;
+            let cell = text(cell).size(TEXT).width(Length::FillPortion(1));
+            row.push(if best == Some(*value) {
+                cell.color(BEST)
+            } else if idx != baseline && worse {
+                cell.color(WORSE)
+            } else {
+                cell
+            })
+        },
+    )
+}
+
+/// Every stat at least one of the items has: weapon damage and attack speed, base stats,
+/// then identifications. Damage ranges compare by their average, identifications by
+/// their base value.
+pub fn stat_rows(gears: &[&Gear]) -> Vec<StatRow> {
+    let row = |label: &str, unit: &'static str, higher_is_better: bool, value: &dyn Fn(&Gear) -> i32| StatRow {
+        label: label.to_string(),
+        unit,
+        values: gears.iter().map(|gear| value(gear)).collect(),
+        higher_is_better,
+        texts: None,
+    };
+
+    let mut rows = Vec::new();
+    for element in ELEMENTS {
+        let ranges: Vec<Option<&str>> = gears
+            .iter()
+            .map(|gear| gear.damage.iter().find(|(label, _)| *label == element).map(|(_, range)| range))
+            .collect();
+        rows.push(StatRow {
+            texts: Some(ranges.iter().map(|range| range.unwrap_or("0-0").to_string()).collect()),
+            ..row(&format!("{} Base Damage", element), "", true, &|gear| {
+                gear.damage
+                    .iter()
+                    .find(|(label, _)| *label == element)
+                    .map_or(0, |(_, range)| average_damage(range))
+            })
+        });
+    }
+    rows.push(StatRow {
+        texts: Some(gears.iter().map(|gear| gear.attack_speed.clone().unwrap_or_default()).collect()),
+        ..row("Base Attack Speed", "", true, &|gear| {
+            gear.attack_speed
+                .as_deref()
+                .and_then(|speed| ATTACK_SPEEDS.iter().position(|known| *known == speed))
+                .map_or(0, |tier| tier as i32 + 1)
+        })
+    });
+
+    rows.extend([
+        row("Combat Level", "", false, &|gear| gear.lvl),
+        row("Base Health", "", true, &|gear| gear.hp),
+        row("Powder Slots", "", true, &|gear| gear.slots as i32),
+    ]);
+    for (idx, (skill, _)) in SkillPoints::default().iter().into_iter().enumerate() {
+        rows.push(row(&format!("{} Min", skill), "", false, &|gear| gear.requirements.iter()[idx].1));
+    }
+    for (idx, (element, _)) in Defenses::default().iter().into_iter().enumerate() {
+        rows.push(row(&format!("{} Defense", element), "", true, &|gear| gear.defenses.iter()[idx].1));
+    }
+    for info in IDENTIFICATIONS {
+        rows.push(row(info.label, info.unit, !info.lower_is_better(), &|gear| {
+            gear.stats
+                .get(info.key)
+                .and_then(serde_json::Value::as_i64)
+                .unwrap_or_default() as i32
+        }));
+    }
+
+    rows.retain(|row| row.values.iter().any(|value| *value != 0));
+    rows
+}
+
+/// Middle of a `min-max` damage range, 0 if it doesn't parse.
+fn average_damage(range: &str) -> i32 {
+    let Some((min, max)) = range.split_once('-') else {
+        return 0;
+    };
+    match (min.trim().parse::<i32>(), max.trim().parse::<i32>()) {
+        (Ok(min), Ok(max)) => (min + max) / 2,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list, names, with_stats};
+
+    fn ring(name: &str) -> Gear {
+        gear(GearType::Ring, name)
+    }
+
+    fn bow(name: &str, damage: &str, attack_speed: &str) -> Gear {
+        let mut bow = gear(GearType::Bow, name);
+        bow.damage.neutral = Some(damage.to_string());
+        bow.attack_speed = Some(attack_speed.to_string());
+        bow
+    }
+
+    fn add(compare: &mut ItemCompare, gear_list: &GearList, names: &[&str]) {
+        for name in names {
+            let _ = compare.update(ItemCompareMessage::Add(name.to_string()), gear_list);
+        }
+    }
+
+    #[test]
+    fn best_respects_direction_and_ties() {
+        let a = with_stats(Gear { hp: 100, ..ring("A") }, &[("sdPct", 10), ("spRaw1", 0)]);
+        let b = with_stats(Gear { hp: 100, ..ring("B") }, &[("sdPct", 5), ("spRaw1", -4)]);
+
+        let rows = stat_rows(&[&a, &b]);
+
+        let labels: Vec<&str> = rows.iter().map(|row| row.label.as_str()).collect();
+        assert_eq!(labels, vec!["Base Health", "Spell Damage", "1st Spell Cost"]);
+        assert_eq!(rows[0].best(), None);
+        assert_eq!(rows[1].best(), Some(10));
+        assert_eq!(rows[2].best(), Some(-4));
+
+        // Stats nobody has aren't rows, and nothing compared means no rows
+        assert!(stat_rows(&[&ring("Plain"), &ring("Plainer")]).is_empty());
+        assert!(stat_rows(&[]).is_empty());
+    }
+
+    #[test]
+    fn weapons_compare_damage_and_attack_speed() {
+        let slow = bow("Slow", "100-140", "SLOW");
+        let fast = bow("Fast", "40-60", "VERY_FAST");
+
+        let rows = stat_rows(&[&slow, &fast]);
+
+        assert_eq!(rows[0].label, "Neutral Base Damage");
+        assert_eq!(rows[0].values, vec![120, 50]);
+        assert_eq!(rows[0].texts, Some(vec!["100-140".to_string(), "40-60".to_string()]));
+        assert_eq!(rows[1].label, "Base Attack Speed");
+        assert_eq!(rows[1].best(), Some(6));
+        for range in ["oops", "", "5-", "-5-10", "99999999999-1"] {
+            assert_eq!(average_damage(range), 0, "{}", range);
+        }
+    }
+
+    #[test]
+    fn actions_follow_names_when_items_leave_items_json() {
+        let mut compare = ItemCompare::default();
+        let mut gear_list = gear_list([ring("A"), ring("B"), ring("C")]);
+        add(&mut compare, &gear_list, &["A", "B", "C"]);
+        gear_list.items.remove(0);
+
+        let _ = compare.update(ItemCompareMessage::Remove("C".to_string()), &gear_list);
+        let _ = compare.update(ItemCompareMessage::SetBaseline("B".to_string()), &gear_list);
+
+        assert_eq!(compare.items, names(&["A", "B"]));
+        assert_eq!(compare.baseline_name().as_deref(), Some("B"));
+
+        // Removing the baseline falls back to the first item
+        let _ = compare.update(ItemCompareMessage::Remove("B".to_string()), &gear_list);
+        let _ = compare.update(ItemCompareMessage::SetBaseline("Gone".to_string()), &gear_list);
+        assert_eq!(compare.baseline_name().as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn a_full_list_keeps_its_baseline() {
+        let mut compare = ItemCompare::default();
+        let gear_list = gear_list(["A", "B", "C", "D", "E", "F"].map(ring));
+        add(&mut compare, &gear_list, &["A", "B", "C", "D", "D", "A"]);
+        assert_eq!(compare.items, vec!["A", "B", "C", "D"]);
+        let _ = compare.update(ItemCompareMessage::SetBaseline("D".to_string()), &gear_list);
+
+        add(&mut compare, &gear_list, &["E", "F"]);
+
+        assert_eq!(compare.items, vec!["A", "B", "D", "F"]);
+        assert_eq!(compare.baseline_name().as_deref(), Some("D"));
+    }
+
+    #[test]
+    fn adding_another_type_starts_over() {
+        let mut compare = ItemCompare::default();
+        let gear_list = gear_list([ring("A"), bow("Bow", "1-2", "FAST")]);
+
+        add(&mut compare, &gear_list, &["A", "Bow"]);
+
+        assert_eq!(compare.items, names(&["Bow"]));
+        add(&mut compare, &gear_list, &["Missing"]);
+        assert_eq!(compare.items, names(&["Bow"]));
+    }
+}
@@ -0,0 +1,356 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use sqlite::State;
+
+use crate::config::sets::SetList;
+use crate::config::{Gear, GearList};
+use crate::item_changelog::{self, Changelog};
+
+pub const ITEMS_PATH: &str = "config/items.json";
+pub const CACHE_PATH: &str = "config/items.db";
+
+/// Bump when `Gear` changes shape so caches written by older builds are rebuilt.
+const CACHE_FORMAT: i64 = 2;
+
+/// items.json as the game API serves it, with the game version it was exported for.
+#[derive(Deserialize)]
+struct ItemsFile {
+    items: Vec<Gear>,
+    #[serde(default)]
+    version: Option<serde_json::Value>,
+    #[serde(default)]
+    sets: SetList,
+}
+
+/// The parsed item list and where it came from.
+#[derive(Debug, Clone)]
+pub struct ItemDb {
+    pub gear_list: GearList,
+    /// Game version items.json was exported for, if the file says.
+    pub game_version: Option<String>,
+    /// SHA-1 of items.json; the cache is only used while it matches.
+    pub hash: String,
+    pub from_cache: bool,
+    /// Differences to the items the cache held, when items.json changed since.
+    pub changelog: Option<Changelog>,
+    /// Set definitions from items.json's `sets`, empty when it has none.
+    pub sets: SetList,
+    /// Why the item cache couldn't be read or rewritten. The items are from items.json
+    /// then, and the next start parses it again.
+    pub cache_error: Option<String>,
+}
+
+/// Loads the items off the UI thread, from the cache when it still matches items.json.
+pub async fn load(items_path: PathBuf, cache_path: PathBuf) -> Result<ItemDb, String> {
+    tokio::task::spawn_blocking(move || load_blocking(&items_path, &cache_path))
+        .await
+        .map_err(|e| format!("Item loading stopped unexpectedly: {}", e))?
+}
+
+/// Reads the cache if it was built from the same items.json, otherwise parses the
+/// file and rebuilds the cache. A cache that can't be read or written is reported in
+/// `cache_error` but doesn't fail the load, items.json stays the source of truth.
+pub fn load_blocking(items_path: &Path, cache_path: &Path) -> Result<ItemDb, String> {
+    let bytes = std::fs::read(items_path).map_err(|e| format!("Failed to read items file: {}", e))?;
+    let hash = sha1_hex(&bytes);
+
+    // A cache of another items.json is what the items were before this one
+    let mut cache_errors = Vec::new();
+    let previous = match read_cache(cache_path) {
+        Ok(Some(item_db)) if item_db.hash == hash => return Ok(item_db),
+        Ok(cached) => cached,
+        Err(e) => {
+            cache_errors.push(format!("Failed to read item cache: {}", e));
+            None
+        }
+    };
+
+    let file: ItemsFile =
+        serde_json::from_slice(&bytes).map_err(|e| format!("Failed to parse items JSON: {}", e))?;
+    let gear_list = GearList { items: file.items };
+    let game_version = file.version.map(|version| match version {
+        serde_json::Value::String(version) => version,
+        other => other.to_string(),
+    });
+    let mut item_db = ItemDb {
+        changelog: previous.map(|previous| Changelog {
+            from_version: previous.game_version,
+            to_version: game_version.clone(),
+            ..item_changelog::diff(&previous.gear_list, &gear_list)
+        }),
+        gear_list,
+        game_version,
+        hash,
+        from_cache: false,
+        sets: file.sets,
+        cache_error: None,
+    };
+
+    if let Err(e) = write_cache(cache_path, &item_db) {
+        cache_errors.push(format!("Failed to write item cache: {}", e));
+    }
+    item_db.cache_error = (!cache_errors.is_empty()).then(|| cache_errors.join("; "));
+    Ok(item_db)
+}
+
+/// Items and set definitions of the items.json at `items_path`.
+pub fn read_items(items_path: &Path) -> Result<(GearList, SetList), String> {
+    let bytes = std::fs::read(items_path).map_err(|e| format!("Failed to read items file: {}", e))?;
+    let file: ItemsFile =
+        serde_json::from_slice(&bytes).map_err(|e| format!("Failed to parse items JSON: {}", e))?;
+    Ok((GearList { items: file.items }, file.sets))
+}
+
+/// Checks that `bytes` is an items.json this app can read and returns how many items it has.
+pub fn validate_items(bytes: &[u8]) -> Result<usize, String> {
+    let file: ItemsFile =
+        serde_json::from_slice(bytes).map_err(|e| format!("Not a valid item list: {}", e))?;
+    if file.items.is_empty() {
+        return Err("The item list is empty".to_string());
+    }
+    Ok(file.items.len())
+}
+
+/// Writes `bytes` next to `items_path` and renames it over the old file, so a failed
+/// write never leaves a half-written items.json behind.
+pub fn replace_items(items_path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut temp_path = items_path.as_os_str().to_owned();
+    temp_path.push(".download");
+    let temp_path = PathBuf::from(temp_path);
+
+    std::fs::write(&temp_path, bytes)
+        .and_then(|_| std::fs::rename(&temp_path, items_path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&temp_path);
+            format!("Failed to replace {}: {}", items_path.display(), e)
+        })
+}
+
+/// `None` when there's no cache yet or it was written by a build with another format.
+fn read_cache(cache_path: &Path) -> Result<Option<ItemDb>, String> {
+    if !cache_path.exists() {
+        return Ok(None);
+    }
+    let connection = sqlite::open(cache_path).map_err(|e| e.to_string())?;
+
+    let meta = |key: &str| -> Result<Option<String>, String> {
+        let mut statement = connection
+            .prepare("SELECT value FROM meta WHERE key = ?")
+            .map_err(|e| e.to_string())?;
+        statement.bind((1, key)).map_err(|e| e.to_string())?;
+        match statement.next().map_err(|e| e.to_string())? {
+            State::Row => Ok(Some(statement.read::<String, _>(0).map_err(|e| e.to_string())?)),
+            State::Done => Ok(None),
+        }
+    };
+    if meta("format")? != Some(CACHE_FORMAT.to_string()) {
+        return Ok(None);
+    }
+    let Some(hash) = meta("hash")? else {
+        return Ok(None);
+    };
+    let game_version = meta("game_version")?;
+    let sets = match meta("sets")? {
+        Some(sets) => serde_json::from_str(&sets).map_err(|e| e.to_string())?,
+        None => SetList::default(),
+    };
+
+    // rowid keeps the items.json order
+    let mut statement = connection
+        .prepare("SELECT data FROM items ORDER BY rowid")
+        .map_err(|e| e.to_string())?;
+    let mut items = Vec::new();
+    while let State::Row = statement.next().map_err(|e| e.to_string())? {
+        let data = statement.read::<String, _>(0).map_err(|e| e.to_string())?;
+        items.push(serde_json::from_str(&data).map_err(|e| e.to_string())?);
+    }
+
+    Ok(Some(ItemDb {
+        gear_list: GearList { items },
+        game_version,
+        hash,
+        from_cache: true,
+        changelog: None,
+        sets,
+        cache_error: None,
+    }))
+}
+
+/// Replaces the cache with `item_db`. Items are indexed by type and name for lookups.
+fn write_cache(cache_path: &Path, item_db: &ItemDb) -> Result<(), String> {
+    // Start over rather than repair, the old file may not even be a database
+    if cache_path.exists() {
+        std::fs::remove_file(cache_path).map_err(|e| e.to_string())?;
+    }
+    let connection = sqlite::open(cache_path).map_err(|e| e.to_string())?;
+    connection
+        .execute(
+            "
+            BEGIN;
+            CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            CREATE TABLE items (
+                name TEXT NOT NULL,
+                type TEXT NOT NULL,
+                tier TEXT NOT NULL,
+                lvl INTEGER NOT NULL,
+                data TEXT NOT NULL
+            );
+            CREATE INDEX items_type ON items (type, lvl);
+            CREATE INDEX items_name ON items (name);
+            ",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let result = (|| {
+        let mut insert = connection
+            .prepare("INSERT INTO items (name, type, tier, lvl, data) VALUES (?, ?, ?, ?, ?)")
+            .map_err(|e| e.to_string())?;
+        for gear in &item_db.gear_list.items {
+            let gear_type = serde_json::to_value(&gear.gear_type).map_err(|e| e.to_string())?;
+            let data = serde_json::to_string(gear).map_err(|e| e.to_string())?;
+            insert.bind((1, gear.name.as_str())).map_err(|e| e.to_string())?;
+            insert.bind((2, gear_type.as_str().unwrap_or_default())).map_err(|e| e.to_string())?;
+            insert.bind((3, gear.tier.as_str())).map_err(|e| e.to_string())?;
+            insert.bind((4, gear.lvl as i64)).map_err(|e| e.to_string())?;
+            insert.bind((5, data.as_str())).map_err(|e| e.to_string())?;
+            insert.next().map_err(|e| e.to_string())?;
+            insert.reset().map_err(|e| e.to_string())?;
+        }
+
+        let mut meta = connection
+            .prepare("INSERT INTO meta (key, value) VALUES (?, ?)")
+            .map_err(|e| e.to_string())?;
+        let format = CACHE_FORMAT.to_string();
+        let sets = serde_json::to_string(&item_db.sets).map_err(|e| e.to_string())?;
+        let mut entries = vec![
+            ("format", format.as_str()),
+            ("hash", item_db.hash.as_str()),
+            ("sets", sets.as_str()),
+        ];
+        if let Some(game_version) = &item_db.game_version {
+            entries.push(("game_version", game_version.as_str()));
+        }
+        for entry in entries {
+            meta.bind(&[entry.0, entry.1][..]).map_err(|e| e.to_string())?;
+            meta.next().map_err(|e| e.to_string())?;
+            meta.reset().map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => connection.execute("COMMIT;").map_err(|e| e.to_string()),
+        Err(e) => {
+            let _ = connection.execute("ROLLBACK;");
+            Err(e)
+        }
+    }
+}
+
+fn sha1_hex(bytes: &[u8]) -> String {
+    Sha1::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEMS_JSON: &str = r#"{
+        "version": "2.0.4",
+        "items": [
+            {"id": 1, "name": "Cumulonimbus", "tier": "Legendary", "type": "helmet", "lvl": 64, "hp": 3000, "sdPct": null},
+            {"id": 2, "name": "Yang", "tier": "Rare", "type": "ring", "lvl": 65, "strReq": 30}
+        ]
+    }"#;
+
+    /// A fresh directory per test so they can run in parallel.
+    fn paths(test: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("item_db_{}_{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        (dir.join("items.json"), dir.join("items.db"))
+    }
+
+    #[test]
+    fn second_load_comes_from_the_cache() {
+        let (items_path, cache_path) = paths("cached");
+        std::fs::write(&items_path, ITEMS_JSON).unwrap();
+
+        let parsed = load_blocking(&items_path, &cache_path).unwrap();
+        let cached = load_blocking(&items_path, &cache_path).unwrap();
+
+        assert!(!parsed.from_cache);
+        assert!(cached.from_cache);
+        assert_eq!(cached.game_version.as_deref(), Some("2.0.4"));
+        let names: Vec<&str> = cached.gear_list.items.iter().map(|gear| gear.name.as_str()).collect();
+        assert_eq!(names, vec!["Cumulonimbus", "Yang"]);
+        assert_eq!(cached.gear_list.items[0].hp, 3000);
+        assert_eq!(cached.gear_list.items[1].requirements.strength, 30);
+    }
+
+    #[test]
+    fn changed_items_json_rebuilds_the_cache() {
+        let (items_path, cache_path) = paths("changed");
+        std::fs::write(&items_path, ITEMS_JSON).unwrap();
+        load_blocking(&items_path, &cache_path).unwrap();
+
+        std::fs::write(&items_path, ITEMS_JSON.replace("Yang", "Yin")).unwrap();
+        let reloaded = load_blocking(&items_path, &cache_path).unwrap();
+
+        assert!(!reloaded.from_cache);
+        assert_eq!(reloaded.gear_list.items[1].name, "Yin");
+        let changelog = reloaded.changelog.unwrap();
+        assert_eq!((changelog.added, changelog.removed), (vec!["Yin".to_string()], vec!["Yang".to_string()]));
+        assert!(load_blocking(&items_path, &cache_path).unwrap().from_cache);
+    }
+
+    #[test]
+    fn unreadable_cache_falls_back_to_items_json() {
+        let (items_path, cache_path) = paths("corrupt");
+        std::fs::write(&items_path, ITEMS_JSON).unwrap();
+        std::fs::write(&cache_path, "not a database").unwrap();
+
+        let item_db = load_blocking(&items_path, &cache_path).unwrap();
+
+        assert_eq!(item_db.gear_list.items.len(), 2);
+        assert!(item_db.cache_error.unwrap().starts_with("Failed to read item cache"));
+        // Rebuilding it fixed the cache
+        let cached = load_blocking(&items_path, &cache_path).unwrap();
+        assert!(cached.from_cache && cached.cache_error.is_none());
+    }
+
+    #[test]
+    fn unwritable_cache_is_reported() {
+        let (items_path, cache_path) = paths("unwritable");
+        std::fs::write(&items_path, ITEMS_JSON).unwrap();
+        let cache_path = cache_path.join("missing_dir").join("items.db");
+
+        let item_db = load_blocking(&items_path, &cache_path).unwrap();
+
+        assert_eq!(item_db.gear_list.items.len(), 2);
+        assert!(item_db.cache_error.unwrap().starts_with("Failed to write item cache"));
+    }
+
+    #[test]
+    fn sets_come_from_items_json_and_the_cache() {
+        let (items_path, cache_path) = paths("sets");
+        std::fs::write(&items_path, ITEMS_JSON).unwrap();
+        assert!(load_blocking(&items_path, &cache_path).unwrap().sets.sets.is_empty());
+
+        let with_sets = ITEMS_JSON.replacen('{', r#"{"sets": {"Yin Yang": {"items": ["Yang"], "bonuses": [{}, {"hp": 5}]}},"#, 1);
+        std::fs::write(&items_path, with_sets).unwrap();
+        let parsed = load_blocking(&items_path, &cache_path).unwrap();
+        let cached = load_blocking(&items_path, &cache_path).unwrap();
+
+        assert!(cached.from_cache);
+        for item_db in [parsed, cached] {
+            assert_eq!(item_db.sets.sets["Yin Yang"].items, vec!["Yang".to_string()]);
+            assert_eq!(item_db.sets.sets["Yin Yang"].bonus(2, "hp"), 5.0);
+        }
+    }
+}
@@ -1,24 +1,34 @@
-use builder::Builder;
-use config::{ConfigFile, Gear, GearList, GearSelections, GearType};
+use builder::{Builder, BuilderProgress};
+use build_config::Config;
+use config::gear_filter::GearFilter;
+use config::{ConfigFile, CONFIG_PATH, FillPools, GearList, GearSelections};
 use db_reader::DBReader;
 use iced::alignment::{Horizontal, Vertical};
 use iced::widget::container;
-use iced::{Element, Length, Renderer, Task, Theme};
-use iced_widget::{button, column, combo_box, pick_list, row, text, Container};
+use iced::{Color, Element, Length, Renderer, Task, Theme};
+use iced_widget::{button, center, column, opaque, pick_list, row, stack, text, Container};
 use intro::Intro;
+use item_db::{CACHE_PATH, ITEMS_PATH};
+use item_compare::ItemCompare;
 use messages::*;
 use search_items::SearchItems;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+mod api;
 mod build_config;
 mod builder;
 mod config;
 mod db_reader;
 mod intro;
+mod item_db;
+mod item_changelog;
+mod item_compare;
 mod messages;
+mod migrations;
 mod search_items;
 mod theme_serde;
+mod wynn_api;
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum Tab {
@@ -29,6 +39,7 @@ pub enum Tab {
     Builder,
     Theme,
     DBReader,
+    ItemCompare,
 }
 
 #[derive(Default)]
@@ -40,6 +51,7 @@ struct Tabs {
     config_file_tab: ConfigFile,
     builder_tab: Builder,
     db_reader_tab: DBReader,
+    item_compare_tab: ItemCompare,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,64 +77,40 @@ impl Tabs {
         };
 
         // === Load Config File ===
-        let config = build_config::load_config("config/config.toml").unwrap_or_default();
-
-        // Parse config items into vectors
-        let helmets = config.items.helmets.iter().map(|s| s.to_string()).collect();
-        let chestplates = config
-            .items
-            .chest_plates
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let leggings = config
-            .items
-            .leggings
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let boots = config.items.boots.iter().map(|s| s.to_string()).collect();
-        let rings = config.items.rings.iter().map(|s| s.to_string()).collect();
-        let bracelets = config
-            .items
-            .bracelets
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let necklaces = config
-            .items
-            .necklaces
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let weapon = config.items.weapon.to_string();
+        let (config, load_error) = match build_config::load_config(CONFIG_PATH) {
+            Ok(config) => (config, None),
+            // No file yet is expected on first run; the defaults get saved on the first edit
+            Err(_) if !Path::new(CONFIG_PATH).exists() => (Config::default(), None),
+            Err(e) => (Config::default(), Some(e)),
+        };
 
         // === Load Gear List ===
-        let (gear_list, error_message) = match GearList::from_json("config/items.json") {
-            Ok(list) => (list, "ok".to_owned()),
-            Err(e) => (
-                GearList {
-                    items: vec![
-                        // Default gear items when loading fails
-                        Gear::default_for_type(GearType::Helmet, "No helmets found", -1),
-                        Gear::default_for_type(GearType::Chestplate, "No chestplates found", -2),
-                        Gear::default_for_type(GearType::Leggings, "No leggings found", -3),
-                        Gear::default_for_type(GearType::Boots, "No boots found", -4),
-                        Gear::default_for_type(GearType::Ring, "No rings found", -5),
-                        Gear::default_for_type(GearType::Bracelet, "No bracelets found", -6),
-                        Gear::default_for_type(GearType::Necklace, "No necklaces found", -7),
-                        Gear::default_for_type(GearType::Spear, "No weapons found", -8),
-                    ],
-                },
-                format!("Error loading items.json: {}", e),
-            ),
-        };
+        // Parsed in the background; the Config File tab fills its lists once it's done
+        let gear_list = GearList::default();
+        let load_items = Task::perform(
+            item_db::load(ITEMS_PATH.into(), CACHE_PATH.into()),
+            Message::ItemsLoaded,
+        );
 
-        let selected_weapon = if weapon.is_empty() {
-            None
-        } else {
-            Some(weapon)
+        let gear_filter = GearFilter::new(&gear_list);
+        let (presets, preset_errors) = config::presets::load_presets();
+        let mut config_file_tab = ConfigFile {
+            // Gear selection states
+            gear: GearSelections::new(&gear_list, &config.items, &gear_filter, config.player.class),
+            gear_filter,
+            fill_pools: FillPools::new(&gear_list),
+            load_error,
+            presets,
+            bundle_path: "config/bundle.toml".to_string(),
+            compare_path: "config/config.old.toml".to_string(),
+            preset_status: (!preset_errors.is_empty())
+                .then(|| Err(format!("Failed to load presets: {}", preset_errors.join("; ")))),
+            items_loading: true,
+            gear_list,
+            config,
+            ..Default::default()
         };
+        config_file_tab.validate();
 
         // === Return Initialized State ===
         (
@@ -130,42 +118,14 @@ impl Tabs {
                 active_tab: Tab::Intro,
                 theme: theme.clone(),
                 // Config File Tab initialization
-                config_file_tab: ConfigFile {
-                    // Gear selection states
-                    gear: GearSelections {
-                        helmets: combo_box::State::new(gear_list.helmets()),
-                        helmet_selections: gear_to_some(helmets),
-                        chestplates: combo_box::State::new(gear_list.chestplates()),
-                        chestplate_selections: gear_to_some(chestplates),
-                        leggings: combo_box::State::new(gear_list.leggings()),
-                        leggings_selections: gear_to_some(leggings),
-                        boots: combo_box::State::new(gear_list.boots()),
-                        boots_selections: gear_to_some(boots),
-                        rings: combo_box::State::new(gear_list.rings()),
-                        rings_selections: gear_to_some(rings),
-                        bracelets: combo_box::State::new(gear_list.bracelets()),
-                        bracelets_selections: gear_to_some(bracelets),
-                        necklaces: combo_box::State::new(gear_list.necklaces()),
-                        necklaces_selections: gear_to_some(necklaces),
-                        weapons: combo_box::State::new(gear_list.weapons()),
-                        selected_weapon,
-                    },
-                    // Error handling
-                    error_message: if error_message != "ok" {
-                        Some(error_message)
-                    } else {
-                        None
-                    },
-                    config,
-                    ..Default::default()
-                },
+                config_file_tab,
                 // Search Tab initialization
                 search_items_tab: SearchItems::default(),
                 builder_tab: Builder::default(),
                 db_reader_tab: DBReader::init(theme.clone()),
                 ..Default::default()
             },
-            Task::none(),
+            load_items,
         )
     }
 
@@ -188,10 +148,44 @@ impl Tabs {
                 let _ = std::fs::write(theme_path, theme_toml);
                 Task::none()
             }
-            Message::Search(search_message) => self.search_items_tab.update(search_message),
+            Message::ItemsLoaded(result) => {
+                self.config_file_tab.items_loaded(result);
+                Task::none()
+            }
+            Message::Search(search_message) => {
+                let submitted = matches!(search_message, SearchMessage::InputSubmitted);
+                let task = self.search_items_tab.update(search_message);
+                if submitted {
+                    self.search_items_tab.find_items(&self.config_file_tab.gear_list);
+                }
+                task
+            }
             Message::Config(config_message) => self.config_file_tab.update(config_message),
-            Message::Builder(builder_message) => self.builder_tab.update(builder_message),
+            // The builder would run against an invalid config, so refuse to start it
+            Message::Builder(BuilderMessage::StartBinary)
+                if !self.config_file_tab.errors.is_empty() || self.config_file_tab.items_loading =>
+            {
+                Task::none()
+            }
+            Message::Builder(builder_message) => {
+                // Time runs so the Config File tab can estimate how long the next one takes
+                match &builder_message {
+                    BuilderMessage::StartBinary => self.config_file_tab.builder_started(),
+                    BuilderMessage::Communication(Ok(BuilderProgress::Done)) => {
+                        self.config_file_tab.builder_finished()
+                    }
+                    BuilderMessage::Communication(Err(_) | Ok(BuilderProgress::Error(_)))
+                    | BuilderMessage::StopBinary => {
+                        self.config_file_tab.run_started = None
+                    }
+                    _ => {}
+                }
+                self.builder_tab.update(builder_message)
+            }
             Message::DBReader(dbreader_message) => self.db_reader_tab.update(dbreader_message),
+            Message::ItemCompare(compare_message) => self
+                .item_compare_tab
+                .update(compare_message, &self.config_file_tab.gear_list),
         }
     }
 
@@ -202,9 +196,12 @@ impl Tabs {
             button("Search").on_press(Message::TabSelected(Tab::Search)),
             button("Config File").on_press(Message::TabSelected(Tab::ConfigFile)),
             button("Builder").on_press(Message::TabSelected(Tab::Builder)),
+            button(text(format!("Compare Items ({})", self.item_compare_tab.items.len())))
+                .on_press(Message::TabSelected(Tab::ItemCompare)),
             button("DB Reader").on_press(Message::TabSelected(Tab::DBReader)),
             button("Theme").on_press(Message::TabSelected(Tab::Theme)),
         ]
+        .push_maybe(self.config_file_tab.items_loading.then(|| text("Loading items...")))
         .spacing(4);
 
         // Create content based on active tab
@@ -229,20 +226,33 @@ impl Tabs {
                     .into()
             }
             Tab::ConfigFile => self.config_file_tab.view(),
-            Tab::Builder => self.builder_tab.view(),
+            Tab::Builder => self.builder_tab.view(&self.config_file_tab.errors),
             Tab::DBReader => self.db_reader_tab.view(),
+            Tab::ItemCompare => self.item_compare_tab.view(&self.config_file_tab.gear_list),
         };
 
         // Main layout
-        column![
+        let main = column![
             tab_buttons,
             container(content)
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .padding(20),
         ]
-        .spacing(20)
-        .into()
+        .spacing(20);
+
+        match self.config_file_tab.load_error_dialog() {
+            // Block the whole window until the broken config is dealt with
+            Some(dialog) => stack![
+                main,
+                opaque(center(dialog).style(|_| container::Style {
+                    background: Some(Color { a: 0.7, ..Color::BLACK }.into()),
+                    ..Default::default()
+                })),
+            ]
+            .into(),
+            None => main.into(),
+        }
     }
 }
 
@@ -311,11 +321,3 @@ fn main() -> iced::Result {
         .theme(|app: &Tabs| app.theme.clone())
         .run_with(Tabs::new)
 }
-
-fn gear_to_some(gear_list: Vec<String>) -> Vec<Option<String>> {
-    let mut selections = vec![None; gear_list.len()];
-    for (i, helmet) in gear_list.iter().enumerate() {
-        selections[i] = Some(helmet.clone());
-    }
-    selections
-}
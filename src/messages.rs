@@ -1,6 +1,7 @@
 use std::fmt::{self, Display};
 
-use crate::{builder::BuilderProgress, Tab};
+use crate::{builder::BuilderProgress, config::{presets::{Preset, PresetMode}, Class, GearSlot, ThresholdSection}, Tab};
+use crate::item_db::ItemDb;
 use iced::Theme;
 use iced_table::table::Column;
 use iced_widget::{scrollable::AbsoluteOffset, text_editor::Action};
@@ -12,85 +13,114 @@ pub enum SearchMessage {
     ItemEditorAction(Action),
 }
 
+#[derive(Debug, Clone)]
+pub enum ItemCompareMessage {
+    Add(String),
+    Remove(String),
+    SetBaseline(String),
+    Clear,
+}
+
 #[derive(Debug, Clone)]
 pub enum GearMessage {
-    HelmetSelected(usize, String),
-    AddHelmet,
-    ChestplateSelected(usize, String),
-    AddChestplate,
-    LeggingsSelected(usize, String),
-    AddLeggings,
-    BootsSelected(usize, String),
-    AddBoots,
-    RingsSelected(usize, String),
-    AddRings,
-    BraceletsSelected(usize, String),
-    AddBracelets,
-    NecklacesSelected(usize, String),
-    AddNecklaces,
+    Select(GearSlot, usize, String),
+    Add(GearSlot),
+    Remove(GearSlot, usize),
     WeaponSelected(String),
-    RemoveHelmet(usize),
-    RemoveChestplate(usize),
-    RemoveLeggings(usize),
-    RemoveBoots(usize),
-    RemoveRings(usize),
-    RemoveBracelets(usize),
-    RemoveNecklaces(usize),
 }
 
 #[derive(Debug, Clone)]
-pub enum PlayerMessage {
-    LevelChanged(String),
-    AvailablePointChanged(String),
-    BaseHpChanged(String),
+pub enum IllegalCombinationMessage {
+    /// Start a new combination with its first item, so no group is ever empty.
+    AddGroup(String),
+    RemoveGroup(usize),
+    AddItem(usize, String),
+    RemoveItem(usize, usize),
 }
 
 #[derive(Debug, Clone)]
-pub enum ThresholdFirstMessage {
-    HpChanged(String),
+pub enum BulkImportMessage {
+    Edit(Action),
+    Import,
 }
 
 #[derive(Debug, Clone)]
-pub enum ThresholdSecondMessage {
-    HprRawChanged(String),
-    HprPctChanged(String),
-    MrChanged(String),
-    LsChanged(String),
-    MsChanged(String),
-    SpdChanged(String),
-    SdRawChanged(String),
-    SdPctChanged(String),
-    HprChanged(String),
-    ExpBonusChanged(String),
+pub enum BundleMessage {
+    PathChanged(String),
+    IncludeItemsToggled(bool),
+    Edit(Action),
+    ExportFile,
+    ExportString,
+    ImportFile,
+    ImportString,
+    Apply,
+    Discard,
 }
 
 #[derive(Debug, Clone)]
-pub enum ThresholdThirdMessage {
-    EarthDefChanged(String),
-    ThunderDefChanged(String),
-    WaterDefChanged(String),
-    FireDefChanged(String),
-    AirDefChanged(String),
+pub enum LoadErrorMessage {
+    Retry,
+    StartFresh,
+    Dismiss,
+}
+
+#[derive(Debug, Clone)]
+pub enum CompareMessage {
+    PathChanged(String),
+    Load,
+    Close,
+    /// Ask before copying the current value at this path into the other file.
+    TakeLeft(String),
+    /// Write the value `TakeLeft` asked about into the other file.
+    ConfirmTakeLeft,
+    CancelTakeLeft,
+    /// Copy the other file's value at this path into the current config.
+    TakeRight(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum PresetMessage {
+    Selected(Preset),
+    Apply(PresetMode),
+    NameChanged(String),
+    Save,
+    Delete,
 }
 
 #[derive(Debug, Clone)]
-pub enum ThresholdFourthMessage {
-    NeutralDamPctChanged(String),
-    EarthDamPctChanged(String),
-    ThunderDamPctChanged(String),
-    WaterDamPctChanged(String),
-    FireDamPctChanged(String),
-    AirDamPctChanged(String),
+pub enum GearFilterMessage {
+    TierToggled(String, bool),
+    MinLevelChanged(String),
+    MaxLevelChanged(String),
+    ClassSelected(Option<Class>),
+    Reset,
 }
 
 #[derive(Debug, Clone)]
-pub enum ThresholdFifthMessage {
-    EarthPointChanged(String),
-    ThunderPointChanged(String),
-    WaterPointChanged(String),
-    FirePointChanged(String),
-    AirPointChanged(String),
-    EhpChanged(String),
+pub enum SkillPointMessage {
+    /// Index into `skill_points::EQUIP_SLOTS` and the pooled item picked for it.
+    Selected(usize, String),
+    Clear,
+}
+
+#[derive(Debug, Clone)]
+pub enum FillPoolsMessage {
+    MinLevelChanged(String),
+    MaxLevelChanged(String),
+    TierToggled(String, bool),
+    SortStatSelected(String),
+    CountChanged(String),
+    Preview,
+    Apply,
+    Discard,
+}
+
+#[derive(Debug, Clone)]
+pub enum PlayerMessage {
+    LevelChanged(String),
+    AvailablePointChanged(String),
+    BaseHpChanged(String),
+    ClassSelected(Option<Class>),
 }
 
 #[derive(Debug, Clone)]
@@ -105,6 +135,18 @@ pub enum HppengMessage {
     DbRetryCountChanged(String),
 }
 
+#[derive(Debug, Clone)]
+pub enum ApiMessage {
+    UrlChanged(String),
+    VersionChanged(String),
+    ModuleChanged(String),
+    QueryChanged(String),
+    TestConnection,
+    ConnectionTested(Result<String, String>),
+    UpdateItems,
+    ItemsUpdated(Result<String, String>),
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     TabSelected(Tab),
@@ -113,6 +155,8 @@ pub enum Message {
     Config(ConfigMessage),
     Builder(BuilderMessage),
     DBReader(DBReaderMessage),
+    ItemCompare(ItemCompareMessage),
+    ItemsLoaded(Result<ItemDb, String>),
 }
 
 #[derive(Debug, Clone)]
@@ -177,11 +221,25 @@ pub enum BuilderMessage {
 #[derive(Debug, Clone)]
 pub enum ConfigMessage {
     Gear(GearMessage),
+    IllegalCombination(IllegalCombinationMessage),
+    BulkImport(BulkImportMessage),
+    FillPools(FillPoolsMessage),
+    GearFilter(GearFilterMessage),
+    SkillPoints(SkillPointMessage),
+    Preset(PresetMessage),
+    Bundle(BundleMessage),
+    Compare(CompareMessage),
+    LoadError(LoadErrorMessage),
+    RawModeToggled(bool),
+    RawEdit(Action),
+    /// Show an item in the detail panel, or close it with `None`.
+    InspectItem(Option<String>),
+    DismissChangelog,
     Player(PlayerMessage),
-    ThresholdFirst(ThresholdFirstMessage),
-    ThresholdSecond(ThresholdSecondMessage),
-    ThresholdThird(ThresholdThirdMessage),
-    ThresholdFourth(ThresholdFourthMessage),
-    ThresholdFifth(ThresholdFifthMessage),
+    ThresholdToggled(ThresholdSection, bool),
+    /// Section, field key and the raw input text.
+    ThresholdChanged(ThresholdSection, &'static str, String),
     Hppeng(HppengMessage),
+    Api(ApiMessage),
+    CombosPerSecondChanged(String),
 }
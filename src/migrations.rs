@@ -0,0 +1,147 @@
+use std::path::{Path, PathBuf};
+
+use crate::build_config::Config;
+
+/// Schema version this build writes. Bump it together with a new entry in `MIGRATIONS`.
+pub const CURRENT_VERSION: u32 = 1;
+
+type Migration = fn(&mut toml::Table) -> Result<(), String>;
+
+/// `MIGRATIONS[n]` upgrades a version `n` table to version `n + 1`.
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [v0_to_v1];
+
+/// Files written before versioning differ only by the missing `version` key.
+fn v0_to_v1(_table: &mut toml::Table) -> Result<(), String> {
+    Ok(())
+}
+
+/// Version of a raw config table; files without the key predate versioning.
+pub fn version(table: &toml::Table) -> Result<u32, String> {
+    match table.get("version") {
+        None => Ok(0),
+        Some(value) => value
+            .as_integer()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| format!("Invalid config version: {}", value)),
+    }
+}
+
+/// Upgrades `table` to `CURRENT_VERSION` one step at a time and returns the version it
+/// started at, or `None` if it was already current.
+pub fn migrate(table: &mut toml::Table) -> Result<Option<u32>, String> {
+    let from = version(table)?;
+    if from > CURRENT_VERSION {
+        return Err(format!(
+            "The config is version {}, but this app only supports up to version {}",
+            from, CURRENT_VERSION
+        ));
+    }
+    if from == CURRENT_VERSION {
+        return Ok(None);
+    }
+
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        migration(table).map_err(|e| format!("Migrating from version {}: {}", step, e))?;
+        table.insert("version".to_string(), toml::Value::Integer(step as i64 + 1));
+    }
+    Ok(Some(from))
+}
+
+/// Parses config TOML of any supported version into the current `Config`.
+pub fn parse_config(content: &str) -> Result<(Config, Option<u32>), String> {
+    let table: toml::Table = toml::from_str(content).map_err(|e| e.to_string())?;
+    config_from_table(table)
+}
+
+/// Like `parse_config`, for a table that was already parsed, e.g. one nested in a bundle.
+pub fn config_from_table(mut table: toml::Table) -> Result<(Config, Option<u32>), String> {
+    let migrated_from = migrate(&mut table)?;
+    let config = table
+        .try_into()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+    Ok((config, migrated_from))
+}
+
+/// Copies `path` next to itself as `<name>.<suffix>.bak` and returns the copy's path.
+pub fn backup(path: impl AsRef<Path>, suffix: &str) -> Result<PathBuf, String> {
+    let path = path.as_ref();
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".{}.bak", suffix));
+    let backup = PathBuf::from(backup);
+
+    std::fs::copy(path, &backup)
+        .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    Ok(backup)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const V0_CONFIG: &str = r#"
+        [items]
+        helmets = []
+        chest_plates = []
+        leggings = []
+        boots = []
+        rings = []
+        bracelets = []
+        necklaces = []
+        weapon = ""
+
+        [player]
+        lvl = 106
+        available_point = 200
+        base_hp = 500
+
+        [hppeng]
+        url_prefix = ""
+        url_suffix = ""
+        log_builds = false
+        db_path = ""
+        migrations_path = ""
+        items_file = ""
+        log_db_errors = false
+        db_retry_count = 3
+    "#;
+
+    #[test]
+    fn unversioned_files_are_upgraded() {
+        let (config, migrated_from) = parse_config(V0_CONFIG).unwrap();
+
+        assert_eq!(migrated_from, Some(0));
+        assert_eq!(config.version, CURRENT_VERSION);
+        assert_eq!(config.player.lvl, 106);
+    }
+
+    #[test]
+    fn current_files_are_left_alone() {
+        let content = format!("version = {}\n{}", CURRENT_VERSION, V0_CONFIG);
+
+        assert_eq!(parse_config(&content).unwrap().1, None);
+    }
+
+    #[test]
+    fn newer_files_are_rejected() {
+        let content = format!("version = {}\n{}", CURRENT_VERSION + 1, V0_CONFIG);
+
+        assert!(parse_config(&content).is_err());
+    }
+
+    #[test]
+    fn new_configs_start_at_the_current_version() {
+        assert_eq!(Config::default().version, CURRENT_VERSION);
+    }
+
+    #[test]
+    fn unknown_keys_survive_a_round_trip() {
+        let content = format!("future_flag = true\n{}\n[future_section]\nkey = 1\n", V0_CONFIG);
+
+        let (config, _) = parse_config(&content).unwrap();
+        let saved = toml::to_string(&config).unwrap();
+        let (reloaded, _) = parse_config(&saved).unwrap();
+
+        assert_eq!(reloaded.extra.get("future_flag"), Some(&toml::Value::Boolean(true)));
+        assert!(reloaded.extra.get("future_section").is_some_and(toml::Value::is_table));
+    }
+}
@@ -2,15 +2,21 @@ use iced::{
     alignment::{Horizontal, Vertical},
     Length, Task,
 };
-use iced_widget::{column, container, text, text_editor::Action, text_input, Container};
+use iced_widget::{button, column, container, row, text, text_editor::Action, text_input, Container};
 use iced_widget::text_editor;
 
-use crate::{Message, SearchMessage};
+use crate::config::GearList;
+use crate::{ItemCompareMessage, Message, SearchMessage};
+
+/// More items than this in one search aren't offered for comparison.
+const MAX_FOUND_ITEMS: usize = 20;
 
 #[derive(Default)]
 pub struct SearchItems {
     pub search_input: String,
     pub search_results: text_editor::Content,
+    /// Known items named in the last search output, offered for comparison.
+    pub found_items: Vec<String>,
 }
 
 impl SearchItems {
@@ -41,7 +47,17 @@ impl SearchItems {
                     .placeholder("Output will appear here...")
                     .on_action(|action| Message::Search(
                         SearchMessage::ItemEditorAction(action)
-                    ))
+                    )),
+                    self.found_items
+                        .iter()
+                        .fold(row![].spacing(10), |row, name| {
+                            row.push(
+                                button(text(format!("+ Compare {}", name)))
+                                    .on_press(Message::ItemCompare(ItemCompareMessage::Add(name.clone())))
+                                    .padding(5),
+                            )
+                        })
+                        .wrap(),
                 ]
                 .spacing(20)
                 .align_x(Horizontal::Center);
@@ -54,6 +70,23 @@ impl SearchItems {
             .into()
     }
 
+    /// Picks out the items named in the search output, in the order they appear.
+    pub fn find_items(&mut self, gear_list: &GearList) {
+        let output = self.search_results.text();
+        let mut found: Vec<(usize, &str)> = gear_list
+            .items
+            .iter()
+            .filter_map(|gear| find_name(&output, &gear.name).map(|pos| (pos, gear.name.as_str())))
+            .collect();
+        found.sort();
+
+        self.found_items = found
+            .into_iter()
+            .take(MAX_FOUND_ITEMS)
+            .map(|(_, name)| name.to_string())
+            .collect();
+    }
+
     pub fn update(&mut self, message: SearchMessage) -> Task<Message> {
         match message {
             SearchMessage::InputChanged(input_text) => {
@@ -117,3 +150,12 @@ impl SearchItems {
         Task::none()
     }
 }
+
+/// Position of `name` in `output` as a whole word, so "Ring" doesn't match inside "Ringlet".
+fn find_name(output: &str, name: &str) -> Option<usize> {
+    output.match_indices(name).map(|(pos, _)| pos).find(|&pos| {
+        let before = output[..pos].chars().next_back();
+        let after = output[pos + name.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
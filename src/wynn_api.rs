@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::config::{Damage, Defenses, Gear, GearList, GearType, SkillPoints, IDENTIFICATIONS};
+
+/// Converts the Wynncraft API's item database, an object of item name to item, into
+/// items.json's item list. Items without a gear type here (tomes, charms, ingredients,
+/// materials, tools) are left out.
+///
+/// The API has no item ids, so items keep the id they have in `previous` and new ones
+/// are numbered after its highest, keeping build hashes pointing at the same items.
+pub fn convert_items(body: &[u8], previous: &GearList) -> Result<Vec<Gear>, String> {
+    let database: Map<String, Value> =
+        serde_json::from_slice(body).map_err(|e| format!("Not a valid item database: {}", e))?;
+    let previous_ids: HashMap<&str, i64> = previous.items.iter().map(|gear| (gear.name.as_str(), gear.id)).collect();
+    let mut next_id = previous.items.iter().map(|gear| gear.id + 1).max().unwrap_or_default();
+
+    Ok(database
+        .iter()
+        .filter_map(|(name, item)| convert_item(name, item))
+        .map(|gear| {
+            let id = previous_ids.get(gear.name.as_str()).copied().unwrap_or_else(|| {
+                next_id += 1;
+                next_id - 1
+            });
+            Gear { id, ..gear }
+        })
+        .collect())
+}
+
+fn convert_item(name: &str, item: &Value) -> Option<Gear> {
+    let subtype = ["armourType", "accessoryType", "weaponType"]
+        .iter()
+        .find_map(|key| item.get(key)?.as_str())?;
+    let gear_type: GearType = serde_json::from_value(Value::String(subtype.to_lowercase())).ok()?;
+    if gear_type == GearType::None {
+        return None;
+    }
+
+    // Indexing a missing key gives `Null`, so absent sections read as zeros
+    let requirements = &item["requirements"];
+    let base = &item["base"];
+    let number = |section: &Value, key: &str| section.get(key).and_then(base_value).unwrap_or_default() as i32;
+
+    let mut gear = Gear::default_for_type(gear_type, name, 0);
+    gear.tier = match item["rarity"].as_str() {
+        None | Some("common") => "Normal".to_string(),
+        Some(rarity) => capitalize(rarity),
+    };
+    gear.lvl = number(requirements, "level");
+    gear.class_req = requirements["classRequirement"]
+        .as_str()
+        .and_then(|class| serde_json::from_value(Value::String(capitalize(class))).ok());
+    gear.requirements = SkillPoints {
+        strength: number(requirements, "strength"),
+        dexterity: number(requirements, "dexterity"),
+        intelligence: number(requirements, "intelligence"),
+        defense: number(requirements, "defence"),
+        agility: number(requirements, "agility"),
+    };
+    gear.hp = number(base, "baseHealth");
+    gear.defenses = Defenses {
+        earth: number(base, "baseEarthDefence"),
+        thunder: number(base, "baseThunderDefence"),
+        water: number(base, "baseWaterDefence"),
+        fire: number(base, "baseFireDefence"),
+        air: number(base, "baseAirDefence"),
+    };
+    gear.slots = item["powderSlots"].as_u64().unwrap_or_default() as u8;
+    gear.major_ids = item["majorIds"]
+        .as_object()
+        .map(|major_ids| major_ids.keys().cloned().collect())
+        .unwrap_or_default();
+    gear.fixed_ids = item["identified"].as_bool().unwrap_or_default();
+    gear.attack_speed = item["attackSpeed"].as_str().map(str::to_uppercase);
+    gear.damage = Damage {
+        neutral: damage(base, "baseDamage"),
+        earth: damage(base, "baseEarthDamage"),
+        thunder: damage(base, "baseThunderDamage"),
+        water: damage(base, "baseWaterDamage"),
+        fire: damage(base, "baseFireDamage"),
+        air: damage(base, "baseAirDamage"),
+    };
+    for info in IDENTIFICATIONS {
+        let value = item["identifications"].get(info.api_key).and_then(base_value);
+        if let Some(value) = value.filter(|value| *value != 0) {
+            gear.stats.insert(info.key.to_string(), value.into());
+        }
+    }
+    Some(gear)
+}
+
+/// Stats are either a plain number or a `{min, raw, max}` roll whose `raw` is the base.
+fn base_value(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| value.get("raw")?.as_i64())
+}
+
+/// A `{min, max}` damage range as items.json writes it, e.g. `45-60`.
+fn damage(base: &Value, key: &str) -> Option<String> {
+    let range = base.get(key)?;
+    Some(format!("{}-{}", range.get("min")?.as_i64()?, range.get("max")?.as_i64()?))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::config::fixtures::{gear, gear_list};
+    use crate::config::Class;
+
+    /// Trimmed from the API's `item/database?fullResult` response.
+    pub(crate) const API_FIXTURE: &str = r#"{
+        "Cumulonimbus": {
+            "internalName": "Cumulonimbus",
+            "type": "armour",
+            "armourType": "helmet",
+            "rarity": "legendary",
+            "powderSlots": 3,
+            "requirements": {"level": 64, "dexterity": 30},
+            "base": {"baseHealth": 3000, "baseAirDefence": 100, "baseEarthDefence": -80},
+            "identifications": {
+                "rawAgility": 10,
+                "spellDamage": {"min": 5, "raw": 15, "max": 20},
+                "walkSpeed": 0
+            }
+        },
+        "Stratiformis": {
+            "type": "weapon",
+            "weaponType": "bow",
+            "attackSpeed": "very_fast",
+            "rarity": "mythic",
+            "identified": true,
+            "requirements": {"level": 94, "classRequirement": "archer", "agility": 80},
+            "base": {"baseDamage": {"min": 40, "raw": 45, "max": 50}, "baseAirDamage": {"min": 10, "raw": 12, "max": 15}},
+            "majorIds": {"Windborne": "Your arrows..."}
+        },
+        "Mining Tome": {"type": "tome", "tomeType": "gathering_tome", "rarity": "common"}
+    }"#;
+
+    #[test]
+    fn api_items_are_converted() {
+        let items = convert_items(API_FIXTURE.as_bytes(), &GearList::default()).unwrap();
+
+        assert_eq!(items.len(), 2);
+        let helmet = &items[0];
+        assert_eq!((helmet.name.as_str(), &helmet.gear_type, helmet.tier.as_str()), ("Cumulonimbus", &GearType::Helmet, "Legendary"));
+        assert_eq!((helmet.lvl, helmet.requirements.dexterity, helmet.hp, helmet.slots), (64, 30, 3000, 3));
+        assert_eq!((helmet.defenses.air, helmet.defenses.earth), (100, -80));
+        assert_eq!(helmet.stat("agi"), 10.0);
+        assert_eq!(helmet.stat("sdPct"), 15.0);
+        assert!(!helmet.stats.contains_key("spd"));
+
+        let bow = &items[1];
+        assert_eq!((&bow.gear_type, bow.class_req, bow.fixed_ids), (&GearType::Bow, Some(Class::Archer), true));
+        assert_eq!(bow.attack_speed.as_deref(), Some("VERY_FAST"));
+        assert_eq!(bow.damage.neutral.as_deref(), Some("40-50"));
+        assert_eq!(bow.damage.air.as_deref(), Some("10-15"));
+        assert_eq!(bow.major_ids, vec!["Windborne".to_string()]);
+    }
+
+    #[test]
+    fn ids_are_kept_across_updates() {
+        let previous = gear_list([
+            Gear { id: 7, ..gear(GearType::Ring, "Removed") },
+            Gear { id: 3, ..gear(GearType::Bow, "Stratiformis") },
+        ]);
+
+        let items = convert_items(API_FIXTURE.as_bytes(), &previous).unwrap();
+
+        let ids: Vec<(&str, i64)> = items.iter().map(|gear| (gear.name.as_str(), gear.id)).collect();
+        assert_eq!(ids, vec![("Cumulonimbus", 8), ("Stratiformis", 3)]);
+    }
+
+    #[test]
+    fn only_objects_are_item_databases() {
+        let previous = GearList::default();
+        assert!(convert_items(b"<html>", &previous).is_err());
+        assert!(convert_items(b"[]", &previous).is_err());
+        assert!(convert_items(b"{}", &previous).unwrap().is_empty());
+    }
+}